@@ -14,6 +14,15 @@ pub trait LoadResource {
     /// have been loaded, it will be inserted as a resource. This ensures that the resource only
     /// exists when the assets are ready.
     fn load_resource<T: Resource + Asset + Clone + FromWorld>(&mut self) -> &mut Self;
+
+    /// This will load the [`Resource`] as an [`Asset`] from a file at `path`, using whichever
+    /// [`AssetLoader`](bevy::asset::AssetLoader) is registered for `T`. The resource is inserted
+    /// once the asset and its dependencies have loaded, and is re-inserted whenever the file is
+    /// hot-reloaded.
+    fn load_resource_from_path<T: Resource + Asset + Clone>(
+        &mut self,
+        path: &'static str,
+    ) -> &mut Self;
 }
 
 impl LoadResource for App {
@@ -34,6 +43,32 @@ impl LoadResource for App {
             }));
         self
     }
+
+    fn load_resource_from_path<T: Resource + Asset + Clone>(
+        &mut self,
+        path: &'static str,
+    ) -> &mut Self {
+        self.init_asset::<T>();
+        self.add_systems(
+            PreUpdate,
+            reload_resource_asset::<T>
+                .after(load_resource_assets)
+                .run_if(resource_exists::<T>),
+        );
+        let world = self.world_mut();
+        let assets = world.resource::<AssetServer>();
+        let handle: Handle<T> = assets.load(path);
+        let mut handles = world.resource_mut::<ResourceHandles>();
+        handles
+            .waiting
+            .push_back((handle.untyped(), |world, handle| {
+                let assets = world.resource::<Assets<T>>();
+                if let Some(value) = assets.get(handle.id().typed::<T>()) {
+                    world.insert_resource(value.clone());
+                }
+            }));
+        self
+    }
 }
 
 /// A function that inserts a loaded resource.
@@ -69,3 +104,19 @@ fn load_resource_assets(world: &mut World) {
         });
     });
 }
+
+/// Re-insert a file-backed resource when its asset is modified (e.g. by the file watcher).
+fn reload_resource_asset<T: Resource + Asset + Clone>(
+    mut commands: Commands,
+    mut asset_events: EventReader<AssetEvent<T>>,
+    assets: Res<Assets<T>>,
+) {
+    for event in asset_events.read() {
+        if let AssetEvent::Modified { id } = event {
+            if let Some(value) = assets.get(*id) {
+                info!("Reloaded {}", T::short_type_path());
+                commands.insert_resource(value.clone());
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use crate::asset_tracking::LoadResource;
+use crate::game::farm::{EnemyObstacle, TileOccupant};
+use crate::game::water::WaterTank;
 use bevy::image::{ImageLoaderSettings, ImageSampler};
 use bevy::prelude::*;
 
@@ -14,6 +16,9 @@ pub fn barn(barn_assets: &BarnAssets) -> impl Bundle {
     (
         Name::new("Barn"),
         Barn,
+        TileOccupant,
+        EnemyObstacle,
+        WaterTank::default(),
         Sprite {
             image: barn_assets.house.clone(),
             ..default()
@@ -24,7 +29,7 @@ pub fn barn(barn_assets: &BarnAssets) -> impl Bundle {
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
-struct Barn;
+pub struct Barn;
 
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
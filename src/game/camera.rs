@@ -0,0 +1,170 @@
+//! Zooming and panning the main camera around the farm.
+//!
+//! Zoom with the mouse wheel or a pinch, and pan by dragging with the middle mouse button or
+//! two fingers, or by holding the cursor at the edge of the window. Edge panning waits while the
+//! cursor is over the UI or the window is in the background.
+
+use crate::game::farm::FARM_SIZE_PX;
+use crate::screens::Screen;
+use crate::{AppSystems, Pause};
+use bevy::input::gestures::PinchGesture;
+use bevy::input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll, MouseScrollUnit};
+use bevy::input::touch::Touch;
+use bevy::picking::hover::HoverMap;
+use bevy::picking::pointer::PointerId;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+/// The smallest projection scale, i.e. the furthest the camera zooms in.
+const MIN_SCALE: f32 = 0.5;
+/// The largest projection scale, i.e. the furthest the camera zooms out.
+const MAX_SCALE: f32 = 1.5;
+/// How much one line of mouse wheel scrolling zooms by.
+const ZOOM_PER_LINE: f32 = 0.1;
+/// How many pixels of smooth scrolling count as one line.
+const PIXELS_PER_LINE: f32 = 50.;
+/// How close to the edge of the window the cursor has to be to pan.
+const EDGE_PAN_MARGIN_PX: f32 = 16.;
+/// How fast edge panning moves, in screen pixels per second.
+const EDGE_PAN_SPEED_PX: f32 = 400.;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (zoom_camera, pan_camera, clamp_camera)
+            .chain()
+            .run_if(in_state(Screen::Gameplay).and(in_state(Pause(false))))
+            .in_set(AppSystems::Update),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), reset_camera);
+}
+
+fn zoom_camera(
+    mut q_camera: Query<&mut Projection, With<Camera2d>>,
+    mouse_scroll: Res<AccumulatedMouseScroll>,
+    mut pinch_events: EventReader<PinchGesture>,
+    touches: Res<Touches>,
+) {
+    let lines = match mouse_scroll.unit {
+        MouseScrollUnit::Line => mouse_scroll.delta.y,
+        MouseScrollUnit::Pixel => mouse_scroll.delta.y / PIXELS_PER_LINE,
+    };
+    let mut zoom = 1. + lines * ZOOM_PER_LINE;
+
+    // Trackpad pinches
+    for pinch in pinch_events.read() {
+        zoom *= 1. + pinch.0;
+    }
+
+    // Touchscreen pinches
+    if let Some((a, b)) = two_touches(&touches) {
+        let previous_distance = a.previous_position().distance(b.previous_position());
+        let distance = a.position().distance(b.position());
+        if previous_distance > 0. {
+            zoom *= distance / previous_distance;
+        }
+    }
+
+    if zoom == 1. || zoom <= 0. {
+        return;
+    }
+    if let Ok(mut projection) = q_camera.single_mut()
+        && let Projection::Orthographic(ortho) = projection.as_mut()
+    {
+        ortho.scale = (ortho.scale / zoom).clamp(MIN_SCALE, MAX_SCALE);
+    }
+}
+
+fn pan_camera(
+    mut q_camera: Query<(&mut Transform, &Projection), With<Camera2d>>,
+    q_windows: Query<&Window, With<PrimaryWindow>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mouse_motion: Res<AccumulatedMouseMotion>,
+    touches: Res<Touches>,
+    time: Res<Time<Real>>,
+    q_interactions: Query<&Interaction>,
+    q_nodes: Query<(), With<Node>>,
+    hover_map: Option<Res<HoverMap>>,
+) {
+    let Ok((mut transform, Projection::Orthographic(ortho))) = q_camera.single_mut() else {
+        return;
+    };
+
+    // How far to move, in screen pixels with y pointing down
+    let mut drag = Vec2::ZERO;
+    if mouse_buttons.pressed(MouseButton::Middle) {
+        drag -= mouse_motion.delta;
+    }
+    if let Some((a, b)) = two_touches(&touches) {
+        drag -= (a.delta() + b.delta()) / 2.;
+    }
+    // Reaching for a button at the edge of the screen shouldn't scroll the farm away
+    let over_ui = q_interactions
+        .iter()
+        .any(|interaction| *interaction != Interaction::None)
+        || hover_map.is_some_and(|hover_map| {
+            hover_map
+                .get(&PointerId::Mouse)
+                .is_some_and(|hits| hits.keys().any(|&entity| q_nodes.contains(entity)))
+        });
+    if let Ok(window) = q_windows.single()
+        && window.focused
+        && !over_ui
+        && let Some(cursor) = window.cursor_position()
+    {
+        let edge_direction = Vec2::new(
+            edge_direction(cursor.x, window.width()),
+            edge_direction(cursor.y, window.height()),
+        );
+        drag += edge_direction * EDGE_PAN_SPEED_PX * time.delta_secs();
+    }
+
+    transform.translation += (Vec2::new(drag.x, -drag.y) * ortho.scale).extend(0.);
+}
+
+/// -1, 0 or 1 depending on whether a cursor coordinate is at either edge of the window.
+fn edge_direction(position: f32, size: f32) -> f32 {
+    if position < EDGE_PAN_MARGIN_PX {
+        -1.
+    } else if position > size - EDGE_PAN_MARGIN_PX {
+        1.
+    } else {
+        0.
+    }
+}
+
+/// Keep the farm in view: the camera can only move as far as the farm extends past the screen.
+fn clamp_camera(
+    mut q_camera: Query<(&mut Transform, &Projection), With<Camera2d>>,
+    q_windows: Query<&Window, With<PrimaryWindow>>,
+) {
+    let (Ok((mut transform, Projection::Orthographic(ortho))), Ok(window)) =
+        (q_camera.single_mut(), q_windows.single())
+    else {
+        return;
+    };
+    let visible_size = window.size() * ortho.scale;
+    let slack = ((FARM_SIZE_PX - visible_size) / 2.).max(Vec2::ZERO);
+    let position = transform.translation.xy().clamp(-slack, slack);
+    if transform.translation.xy() != position {
+        transform.translation = position.extend(transform.translation.z);
+    }
+}
+
+fn reset_camera(mut q_camera: Query<(&mut Transform, &mut Projection), With<Camera2d>>) {
+    for (mut transform, mut projection) in &mut q_camera {
+        transform.translation = Vec2::ZERO.extend(transform.translation.z);
+        if let Projection::Orthographic(ortho) = projection.as_mut() {
+            ortho.scale = 1.;
+        }
+    }
+}
+
+/// The first two fingers on the screen, if there are exactly two.
+fn two_touches(touches: &Touches) -> Option<(&Touch, &Touch)> {
+    let mut iter = touches.iter();
+    match (iter.next(), iter.next(), iter.next()) {
+        (Some(a), Some(b), None) => Some((a, b)),
+        _ => None,
+    }
+}
@@ -17,7 +17,7 @@ pub(super) fn plugin(app: &mut App) {
     app.load_resource::<CoinAssets>();
 
     app.add_systems(
-        Update,
+        FixedUpdate,
         make_coins
             .run_if(resource_exists::<CoinAssets>)
             .in_set(PausableSystems),
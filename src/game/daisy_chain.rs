@@ -0,0 +1,557 @@
+//! Daisy chains: lines of grown daisies, each on a tile next to the last, sold for cash.
+//!
+//! A chain forms from at least [`DAISY_CHAIN_LENGTH`] linked daisies and is drawn across them
+//! while it ripens. If it grows or breaks in that time it starts ripening again, and a long chain
+//! is worth more than the shorter chains it could have been split into.
+//!
+//! A ripe chain is picked by a gnome within reach of it and thrown back to the farmer, relayed by
+//! other gnomes if it's too far to throw in one go. With no gnome in reach, it waits on the vine.
+//! It flies like a seed, so enemies can catch it, and it's only paid for when it arrives.
+
+use crate::game::coin::GetCoinEvent;
+use crate::game::despawn::DespawnOnRestart;
+use crate::game::enemy::Enemy;
+use crate::game::farm::{BankAccount, BankAccountUpdateEvent, TILE_SIZE_PX, gnome_positions};
+use crate::game::ledger::{Transaction, TransactionKind, TransactionSource};
+use crate::game::plant::{GNOME_THROW_RADIUS_PX, GrowthTimer, Plant, PlantType};
+use crate::game::player::{Player, PlayerAssets, throw_path};
+use crate::game::player_animation::{PlayerAnimation, PlayerAnimationState};
+use crate::game::replay::GameTick;
+use crate::game::seed::{SavedFlight, SeedFlight, SeedSprite};
+use crate::theme::palette::DAISY_CHAIN;
+use crate::{AppSystems, DrawSystems, PausableSystems};
+use avian2d::prelude::{LinearVelocity, RigidBody};
+use bevy::prelude::*;
+use bevy_vector_shapes::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const DAISY_CHAIN_LENGTH: usize = 3;
+/// The longest chain that will be looked for.
+const MAX_DAISY_CHAIN_LENGTH: usize = 8;
+/// How far apart two daisies can be and still link: neighbouring tiles, but not diagonals.
+const DAISY_LINK_DISTANCE_PX: f32 = TILE_SIZE_PX * 1.1;
+/// What the shortest chain is worth.
+const DAISY_CHAIN_VALUE: f32 = 10.;
+/// How much faster than linearly the value of a chain grows with its length.
+const DAISY_CHAIN_VALUE_EXPONENT: f32 = 1.5;
+const DAISY_CHAIN_RIPEN_S: f32 = 2.;
+const DAISY_CHAIN_Z_LAYER: f32 = 2.0;
+const DAISY_CHAIN_SCALE: f32 = 0.25;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<DaisyChains>();
+    app.add_event::<SendDaisyChainEvent>();
+
+    app.add_systems(
+        FixedUpdate,
+        (
+            ripen_daisy_chains.in_set(AppSystems::TickTimers),
+            (
+                form_daisy_chains,
+                send_daisy_chains.run_if(resource_exists::<PlayerAssets>),
+                deliver_daisy_chains,
+                intercept_daisy_chains,
+            )
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
+    );
+    app.add_systems(Update, draw_daisy_chains.in_set(DrawSystems));
+}
+
+/// The chains currently on the farm.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct DaisyChains(Vec<DaisyChain>);
+
+impl DaisyChains {
+    pub fn save(&self) -> Vec<SavedDaisyChain> {
+        self.0
+            .iter()
+            .map(|chain| SavedDaisyChain {
+                positions: chain.positions.iter().map(|p| (*p).into()).collect(),
+                ripening_s: chain.ripening.elapsed_secs(),
+            })
+            .collect()
+    }
+
+    /// Rebuild the chains across the loaded daisies, found with `daisy_at`. Chains that have lost
+    /// a daisy are dropped, and will be found again from scratch.
+    pub fn load(saved: &[SavedDaisyChain], daisy_at: impl Fn(Vec2) -> Option<Entity>) -> Self {
+        Self(
+            saved
+                .iter()
+                .filter_map(|saved| {
+                    let positions: Vec<Vec2> =
+                        saved.positions.iter().map(|p| Vec2::from(*p)).collect();
+                    let daisies = positions
+                        .iter()
+                        .map(|p| daisy_at(*p))
+                        .collect::<Option<Vec<Entity>>>()?;
+                    let mut chain = DaisyChain::new(daisies, positions);
+                    chain
+                        .ripening
+                        .set_elapsed(Duration::from_secs_f32(saved.ripening_s));
+                    Some(chain)
+                })
+                .collect(),
+        )
+    }
+}
+
+/// A chain ripening on the farm as it was when the game was saved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedDaisyChain {
+    positions: Vec<(f32, f32)>,
+    ripening_s: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct DaisyChain {
+    /// The daisies in the chain, in order from one end to the other.
+    daisies: Vec<Entity>,
+    positions: Vec<Vec2>,
+    ripening: Timer,
+}
+
+impl DaisyChain {
+    fn new(daisies: Vec<Entity>, positions: Vec<Vec2>) -> Self {
+        Self {
+            daisies,
+            positions,
+            ripening: Timer::from_seconds(DAISY_CHAIN_RIPEN_S, TimerMode::Once),
+        }
+    }
+
+    /// Whether this is the same chain of daisies, read in either direction.
+    fn same_daisies(&self, daisies: &[Entity]) -> bool {
+        self.daisies == daisies || self.daisies.iter().rev().eq(daisies.iter())
+    }
+}
+
+/// Sent when a ripe chain is picked, to be thrown back to the farmer along `path`.
+#[derive(Event, Debug)]
+pub struct SendDaisyChainEvent {
+    pub daisy_entities: Vec<Entity>,
+    pub path: Vec<IVec2>,
+    pub value: f32,
+}
+
+/// A picked daisy chain on its way to the farmer.
+#[derive(Component, Debug, Clone, PartialEq, Default)]
+pub struct DaisyChainParcel {
+    /// The points the chain has still to pass through, ending at the farmer.
+    path: Vec<IVec2>,
+    value: f32,
+}
+
+fn daisy_chain_parcel(
+    player_assets: &PlayerAssets,
+    parcel: DaisyChainParcel,
+    position: Vec2,
+    flight: SeedFlight,
+) -> impl Bundle {
+    (
+        Name::new("Daisy Chain"),
+        parcel,
+        DespawnOnRestart,
+        flight,
+        Transform::from_translation(position.extend(DAISY_CHAIN_Z_LAYER)),
+        Visibility::default(),
+        RigidBody::Kinematic,
+        LinearVelocity::ZERO,
+        children![(
+            Name::new("Daisy Chain Sprite"),
+            SeedSprite,
+            Sprite {
+                image: player_assets.chain.clone(),
+                ..default()
+            },
+            Transform::from_scale(Vec3::splat(DAISY_CHAIN_SCALE)),
+        )],
+    )
+}
+
+/// A daisy chain on its way to the farmer as it was when the game was saved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedDaisyChainParcel {
+    position: (f32, f32),
+    path: Vec<(i32, i32)>,
+    value: f32,
+    flight: SavedFlight,
+}
+
+impl SavedDaisyChainParcel {
+    pub fn new(transform: &Transform, parcel: &DaisyChainParcel, flight: &SeedFlight) -> Self {
+        Self {
+            position: transform.translation.xy().into(),
+            path: parcel.path.iter().map(|p| (*p).into()).collect(),
+            value: parcel.value,
+            flight: flight.save(),
+        }
+    }
+
+    pub fn spawn(&self, commands: &mut Commands, player_assets: &PlayerAssets) {
+        commands.spawn(daisy_chain_parcel(
+            player_assets,
+            DaisyChainParcel {
+                path: self.path.iter().map(|p| IVec2::from(*p)).collect(),
+                value: self.value,
+            },
+            self.position.into(),
+            self.flight.load(),
+        ));
+    }
+}
+
+/// What a chain of `length` daisies sells for.
+pub fn daisy_chain_value(length: usize) -> f32 {
+    DAISY_CHAIN_VALUE * (length as f32 / DAISY_CHAIN_LENGTH as f32).powf(DAISY_CHAIN_VALUE_EXPONENT)
+}
+
+/// Split daisies into chains, longest first, as lists of indices into `positions`.
+fn find_daisy_chains(positions: &[Vec2]) -> Vec<Vec<usize>> {
+    let links: Vec<Vec<usize>> = positions
+        .iter()
+        .map(|a| {
+            (0..positions.len())
+                .filter(|&j| {
+                    let distance = a.distance(positions[j]);
+                    distance > 0. && distance <= DAISY_LINK_DISTANCE_PX
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut used = vec![false; positions.len()];
+    let mut chains = Vec::new();
+    loop {
+        let mut longest = Vec::new();
+        for start in 0..positions.len() {
+            if !used[start] {
+                longest_path(&links, &used, &mut vec![start], &mut longest);
+            }
+        }
+        if longest.len() < DAISY_CHAIN_LENGTH {
+            return chains;
+        }
+        for &i in &longest {
+            used[i] = true;
+        }
+        chains.push(longest);
+    }
+}
+
+/// Extend `path` every way it can go through unused daisies, keeping the longest in `longest`.
+fn longest_path(
+    links: &[Vec<usize>],
+    used: &[bool],
+    path: &mut Vec<usize>,
+    longest: &mut Vec<usize>,
+) {
+    if path.len() > longest.len() {
+        *longest = path.clone();
+    }
+    if path.len() == MAX_DAISY_CHAIN_LENGTH {
+        return;
+    }
+    let last = *path.last().unwrap();
+    for &next in &links[last] {
+        if !used[next] && !path.contains(&next) {
+            path.push(next);
+            longest_path(links, used, path, longest);
+            path.pop();
+        }
+    }
+}
+
+/// Find the chains again whenever a daisy grows or goes.
+fn form_daisy_chains(
+    mut chains: ResMut<DaisyChains>,
+    mut grown: RemovedComponents<GrowthTimer>,
+    mut gone: RemovedComponents<Plant>,
+    q_new_plants: Query<(), (Added<Plant>, Without<GrowthTimer>)>,
+    q_plants: Query<(Entity, &Transform, &Plant), Without<GrowthTimer>>,
+) {
+    // Drain both readers, so old removals don't trigger another search
+    let changed = grown.read().count() + gone.read().count() > 0;
+    if !changed && q_new_plants.is_empty() {
+        return;
+    }
+
+    let daisies: Vec<(Entity, Vec2)> = q_plants
+        .iter()
+        .filter(|(_, _, p)| p.plant_type() == PlantType::Daisy)
+        .map(|(e, t, _)| (e, t.translation.xy()))
+        .collect();
+    let positions: Vec<Vec2> = daisies.iter().map(|(_, p)| *p).collect();
+
+    let old_chains = std::mem::take(&mut chains.0);
+    for chain in find_daisy_chains(&positions) {
+        let entities: Vec<Entity> = chain.iter().map(|&i| daisies[i].0).collect();
+        // A chain that hasn't changed carries on ripening
+        let chain = match old_chains.iter().find(|c| c.same_daisies(&entities)) {
+            Some(old_chain) => old_chain.clone(),
+            None => DaisyChain::new(entities, chain.iter().map(|&i| positions[i]).collect()),
+        };
+        chains.0.push(chain);
+    }
+}
+
+fn ripen_daisy_chains(
+    mut chains: ResMut<DaisyChains>,
+    mut send_events: EventWriter<SendDaisyChainEvent>,
+    q_player: Query<&Transform, With<Player>>,
+    q_grown_plants: Query<(&Transform, &Plant), Without<GrowthTimer>>,
+    time: Res<Time>,
+) {
+    let Ok(player_transform) = q_player.single() else {
+        return;
+    };
+    let gnomes = gnome_positions(&q_grown_plants);
+    chains.0.retain_mut(|chain| {
+        chain.ripening.tick(time.delta());
+        if !chain.ripening.finished() {
+            return true;
+        }
+
+        // The gnomes close enough to any daisy in the chain to pick it, nearest the middle first
+        let position = chain.positions.iter().sum::<Vec2>() / chain.positions.len() as f32;
+        let mut pickers: Vec<IVec2> = gnomes
+            .iter()
+            .copied()
+            .filter(|gnome| {
+                chain
+                    .positions
+                    .iter()
+                    .any(|daisy| daisy.distance(gnome.as_vec2()) <= GNOME_THROW_RADIUS_PX)
+            })
+            .collect();
+        pickers.sort_by(|a, b| {
+            a.as_vec2()
+                .distance(position)
+                .total_cmp(&b.as_vec2().distance(position))
+        });
+        let Some(path) = pickers.iter().find_map(|&picker| {
+            throw_path(
+                picker,
+                gnomes.clone(),
+                player_transform.translation.xy().as_ivec2(),
+                GNOME_THROW_RADIUS_PX,
+                GNOME_THROW_RADIUS_PX,
+            )
+        }) else {
+            // Wait, ripe, for a gnome to grow near enough to pass it on
+            if chain.ripening.just_finished() {
+                info!("No way to throw the daisy chain at {:?} home", position);
+            }
+            return true;
+        };
+        send_events.write(SendDaisyChainEvent {
+            daisy_entities: chain.daisies.clone(),
+            path,
+            value: daisy_chain_value(chain.daisies.len()),
+        });
+        false
+    });
+}
+
+/// Pick ripe chains and throw them towards the farmer.
+fn send_daisy_chains(
+    mut commands: Commands,
+    mut send_events: EventReader<SendDaisyChainEvent>,
+    player_assets: Res<PlayerAssets>,
+) {
+    for ev in send_events.read() {
+        info!(
+            "Sending daisy chain of {} worth {} along {:?}",
+            ev.daisy_entities.len(),
+            ev.value,
+            ev.path
+        );
+        for entity in ev.daisy_entities.iter() {
+            commands.entity(*entity).try_despawn();
+        }
+        let (Some(origin), Some(next)) = (ev.path.first(), ev.path.get(1)) else {
+            continue;
+        };
+        commands.spawn(daisy_chain_parcel(
+            &player_assets,
+            DaisyChainParcel {
+                path: ev.path[1..].to_vec(),
+                value: ev.value,
+            },
+            origin.as_vec2(),
+            SeedFlight::new(origin.as_vec2(), next.as_vec2()),
+        ));
+    }
+}
+
+/// Fly chains from hop to hop, and pay for them when they reach the farmer.
+fn deliver_daisy_chains(
+    mut commands: Commands,
+    mut q_parcels: Query<(
+        Entity,
+        &Transform,
+        &mut LinearVelocity,
+        &mut SeedFlight,
+        &mut DaisyChainParcel,
+    )>,
+    mut q_player_animation: Query<&mut PlayerAnimation, With<Player>>,
+    mut q_bank_account: Query<&mut BankAccount>,
+    mut bank_account_update_events: EventWriter<BankAccountUpdateEvent>,
+    mut get_coin_events: EventWriter<GetCoinEvent>,
+    time: Res<Time>,
+    tick: Res<GameTick>,
+) {
+    for (entity, transform, mut vel, mut flight, mut parcel) in q_parcels.iter_mut() {
+        let position = transform.translation.xy();
+        if !flight.tick(time.delta()) {
+            *vel = LinearVelocity(flight.velocity(position, time.delta()));
+            continue;
+        }
+
+        parcel.path.remove(0);
+        if let Some(next) = parcel.path.first() {
+            *flight = SeedFlight::new(position, next.as_vec2());
+            continue;
+        }
+
+        info!("Daisy chain worth {} delivered", parcel.value);
+        commands.entity(entity).try_despawn();
+        if let Ok(mut player_animation) = q_player_animation.single_mut() {
+            player_animation.update_state(PlayerAnimationState::Mailing);
+        }
+        let Ok(mut bank_account) = q_bank_account.single_mut() else {
+            warn!("No bank account!");
+            return;
+        };
+        let transaction = Transaction::new(
+            TransactionKind::DaisyChainSale,
+            parcel.value,
+            "Sold a daisy chain",
+            Some(TransactionSource::new(Some(entity), "Daisy chain")),
+            *tick,
+        );
+        bank_account.record(transaction.clone());
+        bank_account_update_events.write(BankAccountUpdateEvent(Some(transaction)));
+
+        get_coin_events.write(GetCoinEvent(position.extend(0.)));
+    }
+}
+
+fn intercept_daisy_chains(
+    mut commands: Commands,
+    q_parcels: Query<(Entity, &Transform, &SeedFlight), With<DaisyChainParcel>>,
+    q_enemies: Query<(Entity, &Transform), With<Enemy>>,
+) {
+    for (entity, transform, flight) in q_parcels.iter() {
+        let position = transform.translation.xy();
+        if let Some(enemy) = flight.catcher(position, q_enemies.iter()) {
+            info!("{:?} caught a daisy chain at {:?}", enemy, position);
+            commands.entity(entity).try_despawn();
+        }
+    }
+}
+
+/// Draw each chain across its daisies, thickening as it ripens.
+fn draw_daisy_chains(mut painter: ShapePainter, chains: Res<DaisyChains>) {
+    painter.transform = Transform::IDENTITY;
+    painter.color = DAISY_CHAIN;
+    for chain in &chains.0 {
+        painter.thickness = 2. + 4. * chain.ripening.fraction();
+        for link in chain.positions.windows(2) {
+            painter.line(link[0].extend(0.7), link[1].extend(0.7));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::enemy::EnemyKind;
+    use crate::game::sim::Sim;
+
+    fn tile(x: i32, y: i32) -> Vec2 {
+        IVec2::new(x, y).as_vec2() * TILE_SIZE_PX
+    }
+
+    #[test]
+    fn daisies_only_chain_through_neighbouring_tiles() {
+        // An L of four, plus one diagonal from its end
+        let positions = [tile(0, 0), tile(1, 0), tile(2, 0), tile(2, 1), tile(3, 2)];
+        assert_eq!(find_daisy_chains(&positions), vec![vec![0, 1, 2, 3]]);
+        assert!(find_daisy_chains(&[tile(0, 0), tile(1, 1), tile(2, 2)]).is_empty());
+    }
+
+    #[test]
+    fn long_chains_are_worth_more_than_short_ones_together() {
+        assert_eq!(daisy_chain_value(DAISY_CHAIN_LENGTH), DAISY_CHAIN_VALUE);
+        assert!(daisy_chain_value(6) > 2. * daisy_chain_value(3));
+    }
+
+    /// Three daisies in a row above the farmer, and a gnome below the end of them.
+    const DAISIES: [Vec2; 3] = [
+        Vec2::new(-128., 64.),
+        Vec2::new(0., 64.),
+        Vec2::new(128., 64.),
+    ];
+    const GNOME: Vec2 = Vec2::new(128., -64.);
+
+    /// Sow the daisies and the gnome, and wait until the ripe chain is picked.
+    fn sim_with_a_chain_in_flight() -> Sim {
+        let mut sim = Sim::new();
+        sim.hold_waves();
+        sim.set_stock(&[(PlantType::Daisy, 3), (PlantType::Gnome, 1)]);
+        for daisy in DAISIES {
+            sim.click(daisy);
+        }
+        for daisy in DAISIES {
+            sim.sown_at(daisy);
+        }
+        sim.select_seed(PlantType::Gnome);
+        sim.click(GNOME);
+        sim.sown_at(GNOME);
+        sim.advance_until("the chain is picked", |sim| {
+            sim.daisy_chains_in_flight() > 0
+        });
+        sim
+    }
+
+    fn chain_sales(sim: &mut Sim) -> usize {
+        sim.transactions()
+            .iter()
+            .filter(|transaction| transaction.kind == TransactionKind::DaisyChainSale)
+            .count()
+    }
+
+    #[test]
+    fn ripe_chain_is_thrown_to_the_farmer_and_paid_for_when_it_lands() {
+        let mut sim = sim_with_a_chain_in_flight();
+        for daisy in DAISIES {
+            assert_eq!(sim.plant_at(daisy), None);
+        }
+        let balance = sim.balance();
+        assert_eq!(chain_sales(&mut sim), 0);
+
+        sim.advance_until("the chain lands", |sim| sim.daisy_chains_in_flight() == 0);
+        assert_eq!(chain_sales(&mut sim), 1);
+        assert_eq!(sim.balance(), balance + daisy_chain_value(DAISIES.len()));
+        assert!(sim.farmer_is_mailing());
+    }
+
+    #[test]
+    fn chain_caught_by_an_enemy_is_not_paid_for() {
+        let mut sim = sim_with_a_chain_in_flight();
+        // The gnome's throw takes about half a second; catch the chain as it comes down
+        sim.advance(0.38);
+        sim.spawn_enemy(EnemyKind::Rat, Vec2::new(0., -10.));
+        sim.advance_until("the chain is caught", |sim| {
+            sim.daisy_chains_in_flight() == 0
+        });
+        sim.advance(0.5);
+        assert_eq!(chain_sales(&mut sim), 0);
+        assert!(!sim.farmer_is_mailing());
+    }
+}
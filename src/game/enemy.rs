@@ -3,37 +3,33 @@
 use crate::asset_tracking::LoadResource;
 use crate::audio::sound_effect;
 use crate::game::despawn::DespawnOnRestart;
+use crate::game::enemy_registry::{EnemyDefinition, EnemyRegistry};
+use crate::game::farm::{EnemyObstacle, FarmGrid};
 use crate::game::health::Health;
+use crate::game::inventory::CarriedSeeds;
 use crate::game::lifespan::LifespanTimer;
 use crate::game::physics::GameLayer;
 use crate::game::plant::{
-    Burnable, DRAGONFRUIT_STRENGTH, DamagePlantEvent, GNOME_STRENGTH, GrowthTimer,
-    PINEAPPLE_MAX_GENERATION, PINEAPPLE_SPREAD_DISTANCE, PINEAPPLE_STRENGTH, Plant, PlantType,
-    SowPlantEvent, SpewFireEvent,
+    Burnable, DamagePlantEvent, GrowthTimer, Plant, PlantType, SowPlantEvent, SpewFireEvent,
 };
+use crate::game::plant_registry::PlantRegistry;
 use crate::game::player::Player;
+use crate::game::rng::GameRng;
 use crate::theme::palette::ENEMY_EAT_OUTLINE;
-use crate::{OnPauseSystems, PausableSystems};
-use avian2d::math::TAU;
+use crate::{DrawSystems, OnPauseSystems, PausableSystems};
 use avian2d::prelude::*;
 use bevy::image::{ImageLoaderSettings, ImageSampler};
-use bevy::math::ops::{cos, sin};
+use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
 use bevy_vector_shapes::prelude::*;
-use rand::Rng;
 use rand::prelude::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 
 const ENEMY_RADIUS: f32 = 30.0;
 const ENEMY_DESPAWN_DISTANCE: f32 = 1500.0;
 const EAT_RADIUS_PX: f32 = 80.0;
-const SPAWN_INTERVAL_S: f32 = 1.0;
-
-const ENEMY_MOVE_SPEED: f32 = 120.0;
-const ENEMY_SPAWN_LIMIT: usize = 3;
-
-const BITE_COOLDOWN_S: f32 = 2.5;
-const BITE_STRENGTH: i32 = 1;
-const ENEMY_MAX_HEALTH: i32 = 5;
+const WAYPOINT_EPSILON_PX: f32 = 10.0;
 
 const STAR_LIFETIME_S: f32 = 0.25;
 const STAR_SCALE: f32 = 0.25;
@@ -41,6 +37,8 @@ const STAR_Z_LAYER: f32 = 2.;
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<Enemy>();
+    app.register_type::<EnemyKind>();
+    app.register_type::<EnemyPath>();
 
     app.register_type::<EnemyAssets>();
     app.load_resource::<EnemyAssets>();
@@ -49,33 +47,28 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_systems(Update, freeze_enemies.in_set(OnPauseSystems));
     app.add_systems(
-        Update,
+        FixedUpdate,
         (
-            tick_spawn,
             tick_bite_cooldowns,
-            pursue_plants,
+            (plan_enemy_paths, pursue_plants).chain(),
             damage_enemies,
         )
-            .run_if(resource_exists::<EnemyAssets>)
+            .run_if(
+                resource_exists::<EnemyAssets>
+                    .and(resource_exists::<EnemyRegistry>)
+                    .and(resource_exists::<PlantRegistry>),
+            )
             .in_set(PausableSystems),
     );
 
-    app.add_systems(Update, draw_eat_radius);
-}
-
-pub fn enemy_spawner(transform: Transform, spawn_height: f32) -> impl Bundle {
-    (
-        Name::new("Enemy Spawner"),
-        EnemySpawner { spawn_height },
-        transform,
-        SpawnTimer(Timer::from_seconds(SPAWN_INTERVAL_S, TimerMode::Repeating)),
-    )
+    app.add_systems(Update, draw_eat_radius.in_set(DrawSystems));
 }
 
-fn enemy(spawn_position: Vec3, enemy_assets: &EnemyAssets) -> impl Bundle {
+pub fn enemy(spawn_position: Vec3, definition: &EnemyDefinition) -> impl Bundle {
     (
-        Name::new("Enemy"),
+        Name::new(format!("Enemy ({})", definition.kind)),
         Enemy,
+        definition.kind,
         RigidBody::Kinematic,
         DespawnOnRestart,
         Collider::circle(ENEMY_RADIUS),
@@ -84,11 +77,12 @@ fn enemy(spawn_position: Vec3, enemy_assets: &EnemyAssets) -> impl Bundle {
             [GameLayer::Plant, GameLayer::Enemy, GameLayer::Fireball],
         ),
         Sprite {
-            image: enemy_assets.rat.clone(),
+            image: definition.sprite.clone(),
+            color: definition.tint,
             ..default()
         },
         Burnable,
-        Health::new(ENEMY_MAX_HEALTH),
+        Health::new(definition.max_health),
         Transform::from_translation(spawn_position),
         children![(
             Name::new("Enemy eat collider"),
@@ -100,42 +94,129 @@ fn enemy(spawn_position: Vec3, enemy_assets: &EnemyAssets) -> impl Bundle {
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
-struct Enemy;
+pub struct Enemy;
+
+#[derive(
+    Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, Reflect,
+)]
+#[reflect(Component)]
+pub enum EnemyKind {
+    #[default]
+    Rat,
+    Bunny,
+    Leshy,
+    Mole,
+}
+
+impl std::fmt::Display for EnemyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            EnemyKind::Rat => "rat",
+            EnemyKind::Bunny => "bunny",
+            EnemyKind::Leshy => "leshy",
+            EnemyKind::Mole => "mole",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// An enemy as it was when the game was saved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedEnemy {
+    kind: EnemyKind,
+    position: (f32, f32),
+    health: i32,
+    /// Time until it can bite again, if it's just bitten.
+    bite_cooldown_s: Option<f32>,
+    /// Where the plant it's pursuing is, since entities don't survive a save.
+    target: Option<(f32, f32)>,
+    waypoints: Vec<(f32, f32)>,
+    carried_seeds: Vec<PlantType>,
+}
+
+impl SavedEnemy {
+    /// Save an enemy, along with the position of the plant it's pursuing, if any.
+    pub fn new(
+        kind: EnemyKind,
+        transform: &Transform,
+        health: &Health,
+        bite_cooldown: Option<&BiteCooldown>,
+        path: Option<(Vec2, &EnemyPath)>,
+        carried_seeds: Option<&CarriedSeeds>,
+    ) -> Self {
+        Self {
+            kind,
+            position: transform.translation.xy().into(),
+            health: health.current(),
+            bite_cooldown_s: bite_cooldown.map(|c| c.0.remaining_secs()),
+            target: path.map(|(target, _)| target.into()),
+            waypoints: path.map_or(Vec::new(), |(_, p)| {
+                p.waypoints.iter().map(|w| (*w).into()).collect()
+            }),
+            carried_seeds: carried_seeds.map_or(Vec::new(), |c| c.0.clone()),
+        }
+    }
+
+    /// Respawn the enemy as it was saved, finding the plant it was pursuing with `plant_at`.
+    pub fn spawn(
+        &self,
+        commands: &mut Commands,
+        enemy_registry: &EnemyRegistry,
+        plant_at: impl Fn(Vec2) -> Option<Entity>,
+    ) {
+        let Some(definition) = enemy_registry.get(self.kind) else {
+            warn!("No bestiary entry for {}", self.kind);
+            return;
+        };
+        let mut entity = commands.spawn(enemy(Vec2::from(self.position).extend(1.), definition));
+        entity.insert(Health::new(definition.max_health).with_current(self.health));
+        if let Some(remaining_s) = self.bite_cooldown_s {
+            entity.insert(BiteCooldown(Timer::from_seconds(
+                remaining_s,
+                TimerMode::Once,
+            )));
+        }
+        if let Some(target) = self.target.and_then(|t| plant_at(Vec2::from(t))) {
+            entity.insert(EnemyPath {
+                target,
+                waypoints: self.waypoints.iter().map(|w| Vec2::from(*w)).collect(),
+            });
+        }
+        if !self.carried_seeds.is_empty() {
+            entity.insert(CarriedSeeds(self.carried_seeds.clone()));
+        }
+    }
+}
+
+/// The route an enemy is following to the plant it's pursuing.
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct EnemyPath {
+    target: Entity,
+    /// Tile centres still to visit, in order. Once empty, head straight for the target.
+    waypoints: VecDeque<Vec2>,
+}
+
+impl EnemyPath {
+    pub fn target(&self) -> Entity {
+        self.target
+    }
+}
 
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
+/// Effects shared by every kind of enemy. Each kind's own sprite and sounds are in its
+/// [`EnemyDefinition`].
 pub struct EnemyAssets {
-    #[dependency]
-    rat: Handle<Image>,
-    #[dependency]
-    rat_dead: Handle<Image>,
-    #[dependency]
-    rat_hit: Handle<Image>,
-    #[dependency]
-    rat_walk: Handle<Image>,
     #[dependency]
     star_particles: Vec<Handle<Image>>,
     #[dependency]
-    bite_sounds: Vec<Handle<AudioSource>>,
-    #[dependency]
-    rat_damage_sound: Handle<AudioSource>,
-    #[dependency]
     headbonk_sound: Handle<AudioSource>,
 }
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
-#[reflect(Component)]
-struct EnemySpawner {
-    spawn_height: f32,
-}
-
 #[derive(Component, Debug, Clone, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
-struct SpawnTimer(Timer);
-
-#[derive(Component, Debug, Clone, PartialEq, Eq, Default, Reflect)]
-#[reflect(Component)]
-struct BiteCooldown(Timer);
+pub struct BiteCooldown(Timer);
 
 #[derive(Event, Debug)]
 pub struct DamageEnemyEvent {
@@ -148,34 +229,6 @@ impl FromWorld for EnemyAssets {
     fn from_world(world: &mut World) -> Self {
         let assets = world.resource::<AssetServer>();
         Self {
-            rat: assets.load_with_settings(
-                "images/enemies/rat/rat.png",
-                |settings: &mut ImageLoaderSettings| {
-                    // Use `nearest` image sampling to preserve pixel art style.
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            rat_dead: assets.load_with_settings(
-                "images/enemies/rat/rat_dead.png",
-                |settings: &mut ImageLoaderSettings| {
-                    // Use `nearest` image sampling to preserve pixel art style.
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            rat_hit: assets.load_with_settings(
-                "images/enemies/rat/rat_hit.png",
-                |settings: &mut ImageLoaderSettings| {
-                    // Use `nearest` image sampling to preserve pixel art style.
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            rat_walk: assets.load_with_settings(
-                "images/enemies/rat/rat_walk.png",
-                |settings: &mut ImageLoaderSettings| {
-                    // Use `nearest` image sampling to preserve pixel art style.
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
             star_particles: vec![
                 "images/particles/star/star_04.png",
                 "images/particles/star/star_05.png",
@@ -187,12 +240,6 @@ impl FromWorld for EnemyAssets {
                 })
             })
             .collect::<Vec<Handle<Image>>>(),
-            bite_sounds: vec![
-                assets.load("audio/sound_effects/bite/bite1.ogg"),
-                assets.load("audio/sound_effects/bite/bite2.ogg"),
-                assets.load("audio/sound_effects/bite/bite3.ogg"),
-            ],
-            rat_damage_sound: assets.load("audio/sound_effects/rat_damage.ogg"),
             headbonk_sound: assets.load("audio/sound_effects/headbonk.ogg"),
         }
     }
@@ -204,44 +251,88 @@ fn freeze_enemies(mut q_enemies: Query<&mut LinearVelocity, With<Enemy>>) {
     }
 }
 
-fn tick_spawn(
+/// The plant an enemy goes for out of `plants`, which are sorted nearest first: the nearest plant
+/// it prefers, or just the nearest plant if none of those are planted.
+fn choose_target<'a, T>(
+    plants: &'a [T],
+    definition: &EnemyDefinition,
+    plant_type: impl Fn(&T) -> PlantType,
+) -> Option<&'a T> {
+    plants
+        .iter()
+        .find(|p| definition.prefers(plant_type(p)))
+        .or(plants.first())
+}
+
+/// Plan a path for each enemy to its target plant, re-planning when the target changes or the
+/// farm grid does.
+fn plan_enemy_paths(
     mut commands: Commands,
-    q_enemy_spawners: Query<(&Transform, &mut SpawnTimer, &EnemySpawner)>,
-    q_enemies: Query<&Enemy>,
-    q_plants: Query<&Plant>,
-    time: Res<Time>,
-    enemy_assets: Res<EnemyAssets>,
+    q_enemies: Query<(Entity, &EnemyKind, &Transform, Option<&EnemyPath>), With<Enemy>>,
+    q_plants: Query<(Entity, &Transform, &Plant)>,
+    q_obstacles: Query<&Transform, With<EnemyObstacle>>,
+    farm_grid: Res<FarmGrid>,
+    enemy_registry: Res<EnemyRegistry>,
 ) {
-    for (transform, mut spawn_timer, enemy_spawner) in q_enemy_spawners {
-        spawn_timer.0.tick(time.delta());
-
-        if spawn_timer.0.just_finished() {
-            if q_enemies.iter().len() >= ENEMY_SPAWN_LIMIT {
-                debug!("Not spawning an enemy - limit reached");
-                return;
-            }
-            if q_plants
-                .iter()
-                .filter(|p| p.plant_type() == PlantType::Daisy)
-                .count()
-                == 0
-            {
-                debug!("Not spawning an enemy - no daisies");
-                return;
+    let blocked: HashSet<IVec2> = q_obstacles
+        .iter()
+        .map(|t| FarmGrid::tile_at_unbounded(t.translation.xy()))
+        .collect();
+    let unblocked = HashSet::new();
+
+    for (enemy, kind, enemy_transform, enemy_path) in q_enemies {
+        let Some(definition) = enemy_registry.get(*kind) else {
+            continue;
+        };
+        let enemy_position = enemy_transform.translation.xy();
+        let mut plants: Vec<_> = q_plants.iter().collect();
+        plants.sort_by(|a, b| {
+            let dist_a = a.1.translation.xy().distance_squared(enemy_position);
+            let dist_b = b.1.translation.xy().distance_squared(enemy_position);
+            dist_a.partial_cmp(&dist_b).unwrap()
+        });
+        let Some((plant_entity, plant_transform, _)) =
+            choose_target(&plants, definition, |p| p.2.plant_type()).copied()
+        else {
+            if enemy_path.is_some() {
+                commands.entity(enemy).remove::<EnemyPath>();
             }
+            continue;
+        };
 
-            let rng = &mut rand::thread_rng();
-            let rand_f32: f32 = rng.r#gen();
-            let y_offset = (rand_f32 - 0.5) * enemy_spawner.spawn_height;
-            let mut spawn_position = transform.translation;
-            spawn_position.y += y_offset;
-
-            info!(
-                "Spawning an enemy at {:?}",
-                transform.translation + spawn_position
-            );
-            commands.spawn(enemy(spawn_position, &enemy_assets));
+        let up_to_date = enemy_path.is_some_and(|p| p.target == plant_entity);
+        if up_to_date && !farm_grid.is_changed() {
+            continue;
         }
+
+        let start = FarmGrid::tile_at_unbounded(enemy_position);
+        let goal = FarmGrid::tile_at_unbounded(plant_transform.translation.xy());
+        // Burrowing enemies go straight under obstacles
+        let obstacles = if definition.burrowing {
+            &unblocked
+        } else {
+            &blocked
+        };
+        let waypoints = match FarmGrid::find_path(start, goal, obstacles) {
+            // Skip the tile we're on and the target's own tile
+            Some(tiles) if tiles.len() > 2 => tiles[1..tiles.len() - 1]
+                .iter()
+                .map(|tile| FarmGrid::tile_center(*tile))
+                .collect(),
+            Some(_) => VecDeque::new(),
+            None => {
+                debug!("No path from {:?} to {:?} for {:?}", start, goal, enemy);
+                VecDeque::new()
+            }
+        };
+        debug!(
+            "Enemy {:?} path to {:?}: {:?}",
+            enemy, plant_entity, waypoints
+        );
+        commands.entity(enemy).insert(EnemyPath {
+            target: plant_entity,
+            waypoints,
+        });
     }
 }
 
@@ -250,9 +341,11 @@ fn pursue_plants(
     mut q_enemies: Query<
         (
             Entity,
+            &EnemyKind,
             &Transform,
             &mut LinearVelocity,
             Option<&BiteCooldown>,
+            Option<&mut EnemyPath>,
         ),
         With<Enemy>,
     >,
@@ -263,12 +356,26 @@ fn pursue_plants(
     mut sow_plant_events: EventWriter<SowPlantEvent>,
     mut spew_fire_events: EventWriter<SpewFireEvent>,
     enemy_assets: Res<EnemyAssets>,
+    enemy_registry: Res<EnemyRegistry>,
+    plant_registry: Res<PlantRegistry>,
+    farm_grid: Res<FarmGrid>,
+    mut rng: ResMut<GameRng>,
 ) {
     let Ok(player_transform) = q_player.single() else {
         return;
     };
-    for (enemy, enemy_transform, mut enemy_velocity, optional_bite_cooldown) in q_enemies.iter_mut()
+    for (
+        enemy,
+        kind,
+        enemy_transform,
+        mut enemy_velocity,
+        optional_bite_cooldown,
+        mut enemy_path,
+    ) in q_enemies.iter_mut()
     {
+        let Some(definition) = enemy_registry.get(*kind) else {
+            continue;
+        };
         let dist_from_player =
             (enemy_transform.translation - player_transform.translation).length();
         if dist_from_player > ENEMY_DESPAWN_DISTANCE {
@@ -292,14 +399,14 @@ fn pursue_plants(
 
         if plant_vectors.is_empty() {
             // No plants - Move up
-            *enemy_velocity = LinearVelocity(ENEMY_MOVE_SPEED * Vec2::new(0., 1.));
+            *enemy_velocity = LinearVelocity(definition.move_speed * Vec2::new(0., 1.));
             continue;
         }
 
         // Sort plants by distance from this enemy
         plant_vectors.sort_by(|a, b| a.1.length().partial_cmp(&b.1.length()).unwrap());
         let (plant_entity, plant_vector, plant, plant_transform, is_growing) =
-            plant_vectors.first().unwrap();
+            choose_target(&plant_vectors, definition, |p| p.2.plant_type()).unwrap();
 
         if plant_vector.length() < EAT_RADIUS_PX {
             // Eat the plant
@@ -311,37 +418,46 @@ fn pursue_plants(
 
                 damage_plant_events.write(DamagePlantEvent {
                     plant_entity: *plant_entity,
-                    amount: BITE_STRENGTH,
+                    amount: definition.bite_strength,
                 });
 
                 if !is_growing {
+                    let plant_strength = plant_registry.strength(plant.plant_type());
                     match plant.plant_type() {
                         PlantType::Daisy => {
                             // Do nothing
                         }
+                        PlantType::Pineapple(_) if definition.burrowing => {
+                            // Burrowing enemies come from below, under the spikes
+                        }
                         PlantType::Pineapple(generation) => {
                             // Enemy takes damage
                             damage_enemy_events.write(DamageEnemyEvent {
                                 enemy_entity: enemy,
-                                amount: PINEAPPLE_STRENGTH,
+                                amount: plant_strength,
                                 position: enemy_transform.translation,
                             });
 
                             commands.spawn((
-                                sound_effect(enemy_assets.rat_damage_sound.clone()),
+                                sound_effect(definition.hurt_sound.clone()),
                                 Transform::from_translation(enemy_transform.translation),
                             ));
 
-                            let rng = &mut rand::thread_rng();
-                            let angle: f32 = rng.gen_range(0.0..TAU);
-                            let spawn_vec2 = PINEAPPLE_SPREAD_DISTANCE
-                                * Vec2::new(cos(angle), sin(angle)).normalize();
-                            let spawn_pos = plant_transform.translation.xy() + spawn_vec2;
+                            // Spread into a random free tile next to this pineapple
+                            let spawn_tile = FarmGrid::tile_at(plant_transform.translation.xy())
+                                .and_then(|tile| {
+                                    farm_grid.free_neighbours(tile).choose(&mut *rng).copied()
+                                });
 
-                            if generation < PINEAPPLE_MAX_GENERATION {
+                            let max_generation = plant_registry
+                                .get(plant.plant_type())
+                                .map_or(0, |p| p.max_generation());
+                            if let Some(spawn_tile) = spawn_tile
+                                && generation < max_generation
+                            {
                                 info!("Spawning new pineapple (generation={})", generation + 1);
                                 sow_plant_events.write(SowPlantEvent {
-                                    position: spawn_pos,
+                                    position: FarmGrid::tile_center(spawn_tile),
                                     seed_type: PlantType::Pineapple(generation + 1),
                                 });
                             }
@@ -350,7 +466,7 @@ fn pursue_plants(
                             // Enemy takes damage
                             damage_enemy_events.write(DamageEnemyEvent {
                                 enemy_entity: enemy,
-                                amount: DRAGONFRUIT_STRENGTH,
+                                amount: plant_strength,
                                 position: enemy_transform.translation,
                             });
 
@@ -360,7 +476,7 @@ fn pursue_plants(
                             });
 
                             commands.spawn((
-                                sound_effect(enemy_assets.rat_damage_sound.clone()),
+                                sound_effect(definition.hurt_sound.clone()),
                                 Transform::from_translation(enemy_transform.translation),
                             ));
                         }
@@ -368,7 +484,7 @@ fn pursue_plants(
                             // Enemy takes damage
                             damage_enemy_events.write(DamageEnemyEvent {
                                 enemy_entity: enemy,
-                                amount: GNOME_STRENGTH,
+                                amount: plant_strength,
                                 position: enemy_transform.translation,
                             });
                             commands.spawn((
@@ -382,21 +498,37 @@ fn pursue_plants(
                 commands
                     .entity(enemy)
                     .insert(BiteCooldown(Timer::from_seconds(
-                        BITE_COOLDOWN_S,
+                        definition.bite_cooldown_s,
                         TimerMode::Once,
                     )));
 
                 // Play bite sound
-                let rng = &mut rand::thread_rng();
-                let random_bite_sound = enemy_assets.bite_sounds.choose(rng).unwrap().clone();
-                commands.spawn((
-                    sound_effect(random_bite_sound),
-                    Transform::from_translation(enemy_transform.translation),
-                ));
+                if let Some(bite_sound) = definition.bite_sounds.choose(&mut *rng) {
+                    commands.spawn((
+                        sound_effect(bite_sound.clone()),
+                        Transform::from_translation(enemy_transform.translation),
+                    ));
+                }
             }
         } else {
-            // Move towards the plant
-            *enemy_velocity = LinearVelocity(ENEMY_MOVE_SPEED * plant_vector.xy().normalize());
+            // Follow the planned path, then head straight for the plant
+            let enemy_position = enemy_transform.translation.xy();
+            let mut destination = plant_transform.translation.xy();
+            if let Some(path) = enemy_path.as_mut().filter(|p| p.target == *plant_entity) {
+                if path
+                    .waypoints
+                    .front()
+                    .is_some_and(|w| w.distance(enemy_position) < WAYPOINT_EPSILON_PX)
+                {
+                    path.waypoints.pop_front();
+                }
+                if let Some(waypoint) = path.waypoints.front() {
+                    destination = *waypoint;
+                }
+            }
+            *enemy_velocity = LinearVelocity(
+                definition.move_speed * (destination - enemy_position).normalize_or_zero(),
+            );
         }
     }
 }
@@ -430,6 +562,7 @@ fn damage_enemies(
     mut q_enemies: Query<(Entity, &mut Health), With<Enemy>>,
     mut damage_enemy_events: EventReader<DamageEnemyEvent>,
     enemy_assets: Res<EnemyAssets>,
+    mut rng: ResMut<GameRng>,
 ) {
     for ev in damage_enemy_events.read() {
         for (entity, mut health) in q_enemies.iter_mut() {
@@ -439,7 +572,7 @@ fn damage_enemies(
                     DespawnOnRestart,
                     LifespanTimer(Timer::from_seconds(STAR_LIFETIME_S, TimerMode::Once)),
                     Sprite {
-                        image: random_star_particle(&enemy_assets),
+                        image: random_star_particle(&enemy_assets, &mut rng),
                         ..default()
                     },
                     Transform::from_translation(ev.position.with_z(STAR_Z_LAYER))
@@ -455,7 +588,39 @@ fn damage_enemies(
     }
 }
 
-fn random_star_particle(enemy_assets: &EnemyAssets) -> Handle<Image> {
-    let rng = &mut rand::thread_rng();
+fn random_star_particle(enemy_assets: &EnemyAssets, rng: &mut GameRng) -> Handle<Image> {
     enemy_assets.star_particles.choose(rng).unwrap().clone()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::sim::Sim;
+
+    /// The centre of the tile containing (100, 0).
+    const TILE: Vec2 = Vec2::new(128., 64.);
+
+    #[test]
+    fn rat_biting_pineapple_is_spiked_and_spreads_it() {
+        let mut sim = Sim::new();
+        sim.order(PlantType::Pineapple(0), 1, true);
+        sim.advance(2.5);
+        sim.select_seed(PlantType::Pineapple(0));
+        sim.click(Vec2::new(100., 0.));
+        sim.sown_at(TILE);
+        sim.water(TILE);
+        sim.advance(3.1);
+        assert!(sim.plant_at(TILE).unwrap().grown);
+
+        let rat = sim.spawn_enemy(EnemyKind::Rat, TILE + Vec2::new(60., 0.));
+        sim.advance(0.5);
+        assert_eq!(sim.health(rat), Some(3));
+        assert_eq!(sim.plant_at(TILE).unwrap().health, 4);
+        assert!(
+            sim.plants()
+                .iter()
+                .any(|plant| plant.plant_type == PlantType::Pineapple(1))
+        );
+        assert_eq!(sim.enemy_count(), 1);
+    }
+}
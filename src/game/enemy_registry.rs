@@ -0,0 +1,200 @@
+//! Data-driven enemy stats, loaded from `assets/data/bestiary.enemies.ron`.
+
+use crate::asset_tracking::LoadResource;
+use crate::game::enemy::EnemyKind;
+use crate::game::plant::PlantType;
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::color::HexColorError;
+use bevy::image::{ImageLoaderSettings, ImageSampler};
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+const BESTIARY_PATH: &str = "data/bestiary.enemies.ron";
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<EnemyRegistry>();
+    app.register_asset_loader(EnemyRegistryLoader);
+    app.load_resource_from_path::<EnemyRegistry>(BESTIARY_PATH);
+}
+
+/// Every kind of enemy that can appear in a wave.
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct EnemyRegistry {
+    enemies: Vec<EnemyDefinition>,
+}
+
+#[derive(Debug, Clone, Reflect)]
+pub struct EnemyDefinition {
+    pub kind: EnemyKind,
+    pub max_health: i32,
+    pub move_speed: f32,
+    pub bite_strength: i32,
+    pub bite_cooldown_s: f32,
+    /// The kind of plant this enemy goes for first, if any are planted.
+    pub preferred_plant: Option<PlantType>,
+    /// Burrowing enemies tunnel under obstacles and aren't hurt by pineapple spikes.
+    pub burrowing: bool,
+    pub sprite: Handle<Image>,
+    pub tint: Color,
+    /// Played at random when this enemy bites.
+    pub bite_sounds: Vec<Handle<AudioSource>>,
+    /// Played when a plant hurts this enemy back.
+    pub hurt_sound: Handle<AudioSource>,
+}
+
+impl EnemyRegistry {
+    pub fn get(&self, kind: EnemyKind) -> Option<&EnemyDefinition> {
+        self.enemies.iter().find(|e| e.kind == kind)
+    }
+}
+
+impl EnemyDefinition {
+    pub fn prefers(&self, plant_type: PlantType) -> bool {
+        self.preferred_plant == Some(plant_type.species())
+    }
+}
+
+/// The on-disk format of a single enemy.
+#[derive(Deserialize)]
+struct EnemyDefinitionFile {
+    kind: EnemyKind,
+    max_health: i32,
+    move_speed: f32,
+    bite_strength: i32,
+    bite_cooldown_s: f32,
+    #[serde(default)]
+    preferred_plant: Option<PlantType>,
+    #[serde(default)]
+    burrowing: bool,
+    sprite: String,
+    bite_sounds: Vec<String>,
+    hurt_sound: String,
+    /// A hex colour to tint the sprite with.
+    #[serde(default)]
+    tint: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BestiaryFile {
+    enemies: Vec<EnemyDefinitionFile>,
+}
+
+#[derive(Default)]
+struct EnemyRegistryLoader;
+
+#[derive(Debug, Error)]
+enum EnemyRegistryLoaderError {
+    #[error("Could not read bestiary: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse bestiary: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Invalid tint for {0}: {1}")]
+    Tint(EnemyKind, HexColorError),
+}
+
+impl AssetLoader for EnemyRegistryLoader {
+    type Asset = EnemyRegistry;
+    type Settings = ();
+    type Error = EnemyRegistryLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let bestiary: BestiaryFile = ron::de::from_bytes(&bytes)?;
+
+        let mut enemies = Vec::with_capacity(bestiary.enemies.len());
+        for enemy in bestiary.enemies {
+            let tint = match &enemy.tint {
+                Some(hex) => Srgba::hex(hex)
+                    .map_err(|e| EnemyRegistryLoaderError::Tint(enemy.kind, e))?
+                    .into(),
+                None => Color::WHITE,
+            };
+            enemies.push(EnemyDefinition {
+                kind: enemy.kind,
+                max_health: enemy.max_health,
+                move_speed: enemy.move_speed,
+                bite_strength: enemy.bite_strength,
+                bite_cooldown_s: enemy.bite_cooldown_s,
+                preferred_plant: enemy.preferred_plant.map(|p| p.species()),
+                burrowing: enemy.burrowing,
+                sprite: load_context
+                    .loader()
+                    .with_settings(|settings: &mut ImageLoaderSettings| {
+                        // Use `nearest` image sampling to preserve pixel art style.
+                        settings.sampler = ImageSampler::nearest();
+                    })
+                    .load(enemy.sprite),
+                tint,
+                bite_sounds: enemy
+                    .bite_sounds
+                    .into_iter()
+                    .map(|path| load_context.load(path))
+                    .collect(),
+                hurt_sound: load_context.load(enemy.hurt_sound),
+            });
+        }
+
+        Ok(EnemyRegistry { enemies })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["enemies.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn bestiary() -> BestiaryFile {
+        ron::from_str(include_str!("../../assets/data/bestiary.enemies.ron"))
+            .expect("Could not parse bestiary")
+    }
+
+    #[test]
+    fn bestiary_has_every_enemy() {
+        let bestiary = bestiary();
+        for kind in [
+            EnemyKind::Rat,
+            EnemyKind::Bunny,
+            EnemyKind::Leshy,
+            EnemyKind::Mole,
+        ] {
+            assert!(
+                bestiary.enemies.iter().any(|enemy| enemy.kind == kind),
+                "{} is missing",
+                kind
+            );
+        }
+    }
+
+    #[test]
+    fn bestiary_tints_and_files_are_valid() {
+        let assets = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        for enemy in bestiary().enemies {
+            if let Some(hex) = &enemy.tint {
+                assert!(
+                    Srgba::hex(hex).is_ok(),
+                    "{} has an invalid tint",
+                    enemy.kind
+                );
+            }
+            assert!(!enemy.bite_sounds.is_empty(), "{} can't bite", enemy.kind);
+            for path in [&enemy.sprite, &enemy.hurt_sound]
+                .into_iter()
+                .chain(&enemy.bite_sounds)
+            {
+                assert!(assets.join(path).exists(), "{} is missing", path);
+            }
+        }
+    }
+}
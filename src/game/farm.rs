@@ -1,51 +1,85 @@
-use crate::PausableSystems;
 use crate::asset_tracking::LoadResource;
 use crate::audio::sound_effect;
+use crate::game::coin::GetCoinEvent;
+use crate::game::daisy_chain::{DAISY_CHAIN_LENGTH, DaisyChainParcel};
 use crate::game::despawn::DespawnOnRestart;
-use crate::game::enemy::enemy_spawner;
-use crate::game::plant::{
-    DAISY_CHAIN_LENGTH, GNOME_THROW_RADIUS_PX, GrowthTimer, Plant, PlantType, SeedSelection,
-    plant_collision_check,
-};
+use crate::game::inventory::Inventory;
+use crate::game::ledger::{LedgerSummary, Transaction, TransactionKind, TransactionSource};
+use crate::game::mail_order::MailOrder;
+use crate::game::plant::{GNOME_THROW_RADIUS_PX, GrowthTimer, Plant, PlantType, SeedSelection};
+use crate::game::plant_registry::PlantRegistry;
 use crate::game::player::{
     PLAYER_THROW_MIN_DIST_PX, PLAYER_THROW_RADIUS_PX, Player, PlayerClickEvent, ThrowSeedEvent,
-    throw_path,
+    throw_path, window_to_world,
+};
+use crate::game::player_animation::{PlayerAnimation, PlayerAnimationState};
+use crate::game::replay::{GameTick, ReplayInput};
+use crate::game::save::SaveGame;
+use crate::game::seed::{Seed, SeedPath};
+use crate::game::wave::WaveClearedEvent;
+use crate::theme::palette::{
+    ENDGAME_BUTTON_BACKGROUND, LOSER_BACKGROUND, TILE_HOVER_FREE, TILE_HOVER_OCCUPIED,
+    WINNER_BACKGROUND,
 };
-use crate::game::seed::Seed;
-use crate::theme::palette::{ENDGAME_BUTTON_BACKGROUND, LOSER_BACKGROUND, WINNER_BACKGROUND};
+use crate::{AppSystems, DrawSystems, PausableSystems};
 use bevy::image::{ImageLoaderSettings, ImageSampler};
+use bevy::platform::collections::{HashMap, HashSet};
 use bevy::prelude::*;
 use bevy::sprite::SpriteImageMode::Tiled;
+use bevy::window::PrimaryWindow;
 use bevy_cobweb::prelude::Reactive;
 use bevy_vector_shapes::prelude::*;
-
-const TILE_SIZE_PX: f32 = 128.;
-const FARM_SIZE_TILES: Vec2 = Vec2::new(7., 4.);
-const FARM_SIZE_PX: Vec2 = Vec2::new(
-    FARM_SIZE_TILES.x * TILE_SIZE_PX,
-    FARM_SIZE_TILES.y * TILE_SIZE_PX,
+use pathfinding::prelude::astar;
+use std::collections::VecDeque;
+
+pub const TILE_SIZE_PX: f32 = 128.;
+const FARM_SIZE_TILES: IVec2 = IVec2::new(7, 4);
+pub const FARM_SIZE_PX: Vec2 = Vec2::new(
+    FARM_SIZE_TILES.x as f32 * TILE_SIZE_PX,
+    FARM_SIZE_TILES.y as f32 * TILE_SIZE_PX,
 );
 
+/// How many tiles beyond the edge of the farm enemies may walk through.
+const PATHING_MARGIN_TILES: i32 = 2;
+
 const STARTING_BALANCE: f32 = 10.0;
 pub const WINNING_BALANCE: f32 = 50.0;
 const LOSING_BALANCE: f32 = 0.0;
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<Farm>();
+    app.register_type::<FarmGrid>();
+    app.init_resource::<FarmGrid>();
+    app.add_observer(occupy_tile);
+    app.add_observer(vacate_tile);
     app.add_event::<BankAccountUpdateEvent>();
     app.add_event::<RestartGameEvent>();
+    app.add_event::<SowRejectedEvent>();
+    app.register_type::<ThrowLimits>();
+    app.init_resource::<ThrowLimits>();
+    app.init_resource::<ThrowQueue>();
 
     app.register_type::<FarmAssets>();
     app.load_resource::<FarmAssets>();
-    app.add_systems(Update, draw_outline);
     app.add_systems(
         Update,
+        (draw_outline, draw_hovered_tile).in_set(DrawSystems),
+    );
+    app.add_systems(
+        FixedUpdate,
         (
-            end_game,
-            end_game_button_system,
-            on_player_click,
+            // Judge the game once this tick's orders and deliveries have been made
+            end_game.after(AppSystems::Update),
+            (on_player_click, throw_queued_seeds).chain(),
+            pay_wave_bonuses,
             restart_game,
         )
+            .run_if(resource_exists::<FarmAssets>.and(resource_exists::<PlantRegistry>))
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        Update,
+        end_game_button_system
             .run_if(resource_exists::<FarmAssets>)
             .in_set(PausableSystems),
     );
@@ -55,9 +89,7 @@ pub fn farm(farm_assets: &FarmAssets) -> impl Bundle {
     (
         Name::new("Farm"),
         Farm,
-        BankAccount {
-            balance: STARTING_BALANCE,
-        },
+        BankAccount::new(STARTING_BALANCE),
         Sprite {
             image: farm_assets.grass_a.clone(),
             image_mode: Tiled {
@@ -67,11 +99,7 @@ pub fn farm(farm_assets: &FarmAssets) -> impl Bundle {
             },
             ..default()
         },
-        Transform::from_scale(FARM_SIZE_TILES.extend(1.)),
-        children![enemy_spawner(
-            Transform::from_translation(Vec3::new(FARM_SIZE_PX.x * 0.5, 0., 0.)),
-            FARM_SIZE_PX.y * 0.9
-        )],
+        Transform::from_scale(FARM_SIZE_TILES.as_vec2().extend(1.)),
     )
 }
 
@@ -79,6 +107,104 @@ pub fn farm(farm_assets: &FarmAssets) -> impl Bundle {
 #[reflect(Component)]
 struct Farm;
 
+/// Marks an entity that occupies the farm tile it stands on, so nothing else can be planted there.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct TileOccupant;
+
+/// Marks an entity whose tile enemies must path around.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct EnemyObstacle;
+
+/// The farm's tiles and whatever occupies them.
+///
+/// Tile `(0, 0)` is the bottom-left tile of the farm.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct FarmGrid {
+    occupants: HashMap<IVec2, Entity>,
+}
+
+impl FarmGrid {
+    /// The tile containing a world position, or `None` if it's outside the farm.
+    pub fn tile_at(position: Vec2) -> Option<IVec2> {
+        let tile = Self::tile_at_unbounded(position);
+        Self::in_bounds(tile).then_some(tile)
+    }
+
+    /// The tile containing a world position, which may be outside the farm.
+    pub fn tile_at_unbounded(position: Vec2) -> IVec2 {
+        ((position + FARM_SIZE_PX / 2.) / TILE_SIZE_PX)
+            .floor()
+            .as_ivec2()
+    }
+
+    /// The world position of the centre of a tile.
+    pub fn tile_center(tile: IVec2) -> Vec2 {
+        (tile.as_vec2() + 0.5) * TILE_SIZE_PX - FARM_SIZE_PX / 2.
+    }
+
+    /// Every tile on the farm.
+    pub fn tiles() -> impl Iterator<Item = IVec2> {
+        (0..FARM_SIZE_TILES.y).flat_map(|y| (0..FARM_SIZE_TILES.x).map(move |x| IVec2::new(x, y)))
+    }
+
+    pub fn in_bounds(tile: IVec2) -> bool {
+        tile.cmpge(IVec2::ZERO).all() && tile.cmplt(FARM_SIZE_TILES).all()
+    }
+
+    /// The in-bounds tiles sharing an edge with `tile`.
+    pub fn neighbours(tile: IVec2) -> impl Iterator<Item = IVec2> {
+        [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
+            .into_iter()
+            .map(move |offset| tile + offset)
+            .filter(|t| Self::in_bounds(*t))
+    }
+
+    pub fn occupant(&self, tile: IVec2) -> Option<Entity> {
+        self.occupants.get(&tile).copied()
+    }
+
+    pub fn is_occupied(&self, tile: IVec2) -> bool {
+        self.occupants.contains_key(&tile)
+    }
+
+    /// Find the shortest path of tiles from `start` to `goal` that doesn't pass through any
+    /// `blocked` tile. The goal itself may be blocked. Paths may leave the farm by up to
+    /// [`PATHING_MARGIN_TILES`], and a start tile further out than that is pulled inside it.
+    pub fn find_path(start: IVec2, goal: IVec2, blocked: &HashSet<IVec2>) -> Option<Vec<IVec2>> {
+        let min = IVec2::splat(-PATHING_MARGIN_TILES);
+        let max = FARM_SIZE_TILES + IVec2::splat(PATHING_MARGIN_TILES - 1);
+        let start = start.clamp(min, max);
+
+        let successors = |tile: &IVec2| -> Vec<(IVec2, i32)> {
+            [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
+                .into_iter()
+                .map(|offset| *tile + offset)
+                .filter(|t| t.cmpge(min).all() && t.cmple(max).all())
+                .filter(|t| *t == goal || !blocked.contains(t))
+                .map(|t| (t, 1))
+                .collect()
+        };
+
+        let astar_result = astar(
+            &start,
+            successors,
+            |t| (goal - *t).abs().element_sum(),
+            |t| *t == goal,
+        )?;
+        Some(astar_result.0)
+    }
+
+    /// The in-bounds neighbours of `tile` that nothing occupies.
+    pub fn free_neighbours(&self, tile: IVec2) -> Vec<IVec2> {
+        Self::neighbours(tile)
+            .filter(|t| !self.is_occupied(*t))
+            .collect()
+    }
+}
+
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct FarmAssets {
@@ -104,26 +230,50 @@ struct EndGameDisplay;
 #[reflect(Component)]
 struct EndGameRestartButton;
 
-#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Reflect)]
+/// The farmer's money, and every transaction that's changed it this game.
+#[derive(Component, Debug, Clone, Default, PartialEq, Reflect)]
 #[reflect(Component)]
 pub struct BankAccount {
     balance: f32,
+    transactions: Vec<Transaction>,
 }
 
+/// Sent when the balance changes, with the transaction that changed it, if any.
 #[derive(Event, Debug, Default)]
-pub struct BankAccountUpdateEvent;
+pub struct BankAccountUpdateEvent(pub Option<Transaction>);
 
 impl BankAccount {
+    fn new(balance: f32) -> Self {
+        Self {
+            balance,
+            transactions: Vec::new(),
+        }
+    }
+
     pub fn balance(&self) -> f32 {
         self.balance
     }
 
-    fn deduct(&mut self, amount: f32) {
-        self.balance -= amount;
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
     }
 
-    pub fn credit(&mut self, amount: f32) {
-        self.balance += amount;
+    /// Start the account afresh with `balance` and no transactions, for a new game.
+    pub fn restart(&mut self, balance: f32) {
+        *self = Self::new(balance);
+    }
+
+    /// Pick the account up as it was saved, ledger and all.
+    pub fn load(&mut self, balance: f32, transactions: Vec<Transaction>) {
+        self.balance = balance;
+        self.transactions = transactions;
+    }
+
+    /// Apply a transaction to the balance and keep it in the ledger.
+    pub fn record(&mut self, transaction: Transaction) {
+        info!("{}", transaction);
+        self.balance += transaction.amount;
+        self.transactions.push(transaction);
     }
 }
 
@@ -174,6 +324,28 @@ impl FromWorld for FarmAssets {
     }
 }
 
+fn occupy_tile(
+    trigger: Trigger<OnAdd, TileOccupant>,
+    q_transforms: Query<&Transform>,
+    mut farm_grid: ResMut<FarmGrid>,
+) {
+    let entity = trigger.target();
+    let Ok(transform) = q_transforms.get(entity) else {
+        return;
+    };
+    if let Some(tile) = FarmGrid::tile_at(transform.translation.xy()) {
+        debug!("{:?} occupies tile {:?}", entity, tile);
+        farm_grid.occupants.insert(tile, entity);
+    }
+}
+
+fn vacate_tile(trigger: Trigger<OnRemove, TileOccupant>, mut farm_grid: ResMut<FarmGrid>) {
+    let entity = trigger.target();
+    farm_grid
+        .occupants
+        .retain(|_, occupant| *occupant != entity);
+}
+
 fn draw_outline(mut painter: ShapePainter, q_farm: Query<&Farm>) {
     if q_farm.single().is_ok() {
         painter.transform = Transform::default();
@@ -183,130 +355,351 @@ fn draw_outline(mut painter: ShapePainter, q_farm: Query<&Farm>) {
     }
 }
 
+fn draw_hovered_tile(
+    mut painter: ShapePainter,
+    q_windows: Query<&Window, With<PrimaryWindow>>,
+    q_camera: Query<(&Camera, &GlobalTransform)>,
+    q_farm: Query<&Farm>,
+    farm_grid: Res<FarmGrid>,
+) {
+    if q_farm.single().is_err() {
+        return;
+    }
+    let (Ok(window), Ok((camera, camera_transform))) = (q_windows.single(), q_camera.single())
+    else {
+        return;
+    };
+    let Some(tile) = window
+        .cursor_position()
+        .and_then(|p| window_to_world(p, camera, camera_transform))
+        .and_then(FarmGrid::tile_at)
+    else {
+        return;
+    };
+
+    painter.transform = Transform::from_translation(FarmGrid::tile_center(tile).extend(0.5));
+    painter.hollow = true;
+    painter.thickness = 2.0;
+    painter.color = if farm_grid.is_occupied(tile) {
+        TILE_HOVER_OCCUPIED
+    } else {
+        TILE_HOVER_FREE
+    };
+    painter.rect(Vec2::splat(TILE_SIZE_PX));
+}
+
+/// Queue a throw at the clicked tile, to be made once the farmer is free.
 fn on_player_click(
     mut commands: Commands,
     mut click_events: EventReader<PlayerClickEvent>,
-    mut throw_seed_events: EventWriter<ThrowSeedEvent>,
-    q_player: Query<&Transform, With<Player>>,
-    q_seeds: Query<Entity, With<Seed>>,
     q_seed_selection: Reactive<SeedSelection>,
     q_farm: Query<&Farm>,
-    q_plants: Query<(&Transform, &Plant)>,
-    q_grown_plants: Query<(&Transform, &Plant), Without<GrowthTimer>>,
-    mut q_bank_account: Query<&mut BankAccount>,
-    mut bank_account_update_events: EventWriter<BankAccountUpdateEvent>,
+    mut throw_queue: ResMut<ThrowQueue>,
+    throw_limits: Res<ThrowLimits>,
+    mut sow_rejected_events: EventWriter<SowRejectedEvent>,
     farm_assets: Res<FarmAssets>,
 ) {
     if q_farm.single().is_ok() {
         for click_event in click_events.read() {
-            let click_position = click_event.0;
+            let rejection = match FarmGrid::tile_at(click_event.0) {
+                None => SowRejection::OutOfBounds,
+                Some(_) if throw_queue.is_full(&throw_limits) => SowRejection::QueueFull,
+                Some(tile) => {
+                    let (_, seed_selection) = q_seed_selection.single();
+                    throw_queue.0.push_back(QueuedThrow {
+                        tile,
+                        seed_type: seed_selection.seed_type(),
+                    });
+                    continue;
+                }
+            };
+            info!("Can't queue a throw at {:?}: {}", click_event.0, rejection);
+            commands.spawn((
+                sound_effect(farm_assets.invalid_sound.clone()),
+                Transform::from_translation(click_event.0.extend(0.)),
+            ));
+            sow_rejected_events.write(SowRejectedEvent {
+                rejection,
+                position: click_event.0,
+            });
+        }
+    }
+}
 
-            let (_, seed_selection) = q_seed_selection.single();
-            let seed_type = seed_selection.seed_type();
+/// Throw the next queued seed, if the farmer has finished the last throw and has a hand free.
+fn throw_queued_seeds(
+    mut commands: Commands,
+    mut throw_seed_events: EventWriter<ThrowSeedEvent>,
+    mut q_player: Query<(&Transform, &mut PlayerAnimation, &mut Inventory), With<Player>>,
+    q_seeds: Query<&SeedPath, With<Seed>>,
+    mut throw_queue: ResMut<ThrowQueue>,
+    throw_limits: Res<ThrowLimits>,
+    farm_grid: Res<FarmGrid>,
+    q_grown_plants: Query<(&Transform, &Plant), Without<GrowthTimer>>,
+    mut sow_rejected_events: EventWriter<SowRejectedEvent>,
+    farm_assets: Res<FarmAssets>,
+) {
+    let Ok((player_transform, mut player_animation, mut inventory)) = q_player.single_mut() else {
+        return;
+    };
+    if player_animation.is_busy() || q_seeds.iter().count() >= throw_limits.max_seeds_in_flight {
+        return;
+    }
+    let Some(QueuedThrow { tile, seed_type }) = throw_queue.0.pop_front() else {
+        return;
+    };
+    let plan = plan_throw(
+        tile,
+        player_transform.translation.xy().as_ivec2(),
+        gnome_positions(&q_grown_plants),
+        farm_grid.is_occupied(tile) || is_targeted(tile, &q_seeds),
+        false,
+        inventory.count(seed_type) > 0,
+    );
+    match (plan.path, plan.problem) {
+        (Some(path), None) => {
+            inventory.take(seed_type);
+
+            // The farmer can't throw again until this throw's animation is over
+            let left = path.get(1).is_some_and(|next| next.x < path[0].x);
+            player_animation.update_state(PlayerAnimationState::Planting(left));
+            throw_seed_events.write(ThrowSeedEvent {
+                from_player: true,
+                path,
+                seed_type,
+            });
+        }
+        (_, rejection) => {
+            let rejection = rejection.unwrap_or(SowRejection::NoThrowPath);
+            info!("Can't sow at {:?}: {}", plan.target, rejection);
+            commands.spawn((
+                sound_effect(farm_assets.invalid_sound.clone()),
+                Transform::from_translation(plan.target.extend(0.)),
+            ));
+            sow_rejected_events.write(SowRejectedEvent {
+                rejection,
+                position: plan.target,
+            });
+        }
+    }
+}
 
-            let mut can_sow = true;
-            if q_seeds.iter().count() > 0 {
-                info!("Seed already in flight");
-                can_sow = false;
-            }
+/// Whether a seed in flight is already headed for a tile.
+pub fn is_targeted(tile: IVec2, q_seeds: &Query<&SeedPath, With<Seed>>) -> bool {
+    q_seeds
+        .iter()
+        .filter_map(SeedPath::destination)
+        .any(|destination| FarmGrid::tile_at(destination.as_vec2()) == Some(tile))
+}
 
-            let Ok(player_transform) = q_player.single() else {
-                error!("No player found!");
-                return;
-            };
+/// How fast the farmer is allowed to throw.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Resource)]
+pub struct ThrowLimits {
+    /// How many seeds the farmer's throws may have in the air at once.
+    pub max_seeds_in_flight: usize,
+    /// How many clicks may wait for the farmer before more are refused.
+    pub max_queued: usize,
+}
 
-            let player_position = player_transform.translation.xy().as_ivec2();
-            if click_position.x.abs() > FARM_SIZE_PX.x / 2.
-                || click_position.y.abs() > FARM_SIZE_PX.y / 2.
-            {
-                info!("Click out of bounds");
-                can_sow = false;
-            }
-            if (click_position - player_position.as_vec2()).length() < PLAYER_THROW_MIN_DIST_PX {
-                info!("Click too close to farmer");
-                can_sow = false;
-            }
+impl Default for ThrowLimits {
+    fn default() -> Self {
+        Self {
+            max_seeds_in_flight: 3,
+            max_queued: 5,
+        }
+    }
+}
+
+/// Throws the farmer has been asked to make, in the order they were clicked.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ThrowQueue(pub VecDeque<QueuedThrow>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueuedThrow {
+    pub tile: IVec2,
+    pub seed_type: PlantType,
+}
+
+impl ThrowQueue {
+    pub fn is_full(&self, limits: &ThrowLimits) -> bool {
+        self.0.len() >= limits.max_queued
+    }
+
+    pub fn contains(&self, tile: IVec2) -> bool {
+        self.0.iter().any(|queued| queued.tile == tile)
+    }
 
-            let gnome_positions: Vec<IVec2> = q_grown_plants
+    pub fn save(&self) -> Vec<((i32, i32), PlantType)> {
+        self.0
+            .iter()
+            .map(|queued| (queued.tile.into(), queued.seed_type))
+            .collect()
+    }
+
+    pub fn load(saved: &[((i32, i32), PlantType)]) -> Self {
+        Self(
+            saved
                 .iter()
-                .filter(|(_, p)| p.plant_type() == PlantType::Gnome)
-                .map(|(t, _)| t.translation.xy().as_ivec2())
-                .collect();
-
-            let seed_path = throw_path(
-                player_position,
-                gnome_positions,
-                click_position.as_ivec2(),
-                PLAYER_THROW_RADIUS_PX,
-                GNOME_THROW_RADIUS_PX,
-            );
-
-            if seed_path.is_none() {
-                info!(
-                    "No path to throw from {:?} to {:?}",
-                    player_position, click_position
-                );
-
-                can_sow = false;
-            }
+                .map(|(tile, seed_type)| QueuedThrow {
+                    tile: IVec2::from(*tile),
+                    seed_type: *seed_type,
+                })
+                .collect(),
+        )
+    }
+}
 
-            for (plant_transform, plant) in q_plants.iter() {
-                let plant_position = plant_transform.translation.xy();
-                if plant_collision_check(plant_position, click_position, plant.plant_type()) {
-                    // Plant already here
-                    println!(
-                        "Can't plant at {:?} - plant already present at {:?}",
-                        click_position, plant_position
-                    );
-                    can_sow = false;
-                }
-            }
+/// Why a click on the farm didn't sow a seed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum SowRejection {
+    /// The click was outside the farm.
+    OutOfBounds,
+    /// The tile is too close for the farmer to throw to.
+    TooCloseToFarmer,
+    /// The tile is out of range of the farmer and every gnome.
+    NoThrowPath,
+    /// Something is already planted or standing on the tile.
+    Occupied,
+    /// There are none of the seed left in the inventory.
+    OutOfSeeds,
+    /// The farmer already has as many throws queued up as they can remember.
+    QueueFull,
+}
 
-            let Ok(mut bank_account) = q_bank_account.single_mut() else {
-                warn!("No bank account!");
-                return;
-            };
-            info!(
-                "To plant {:?} would cost {}. We have {}",
-                seed_type,
-                seed_type.price(),
-                bank_account.balance()
-            );
-            if bank_account.balance() < seed_type.price() {
-                can_sow = false;
-                info!("Can't afford seed");
-            }
+impl std::fmt::Display for SowRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SowRejection::OutOfBounds => write!(f, "Out of bounds"),
+            SowRejection::TooCloseToFarmer => write!(f, "Too close to the farmer"),
+            SowRejection::NoThrowPath => write!(f, "Out of range"),
+            SowRejection::Occupied => write!(f, "Tile already occupied"),
+            SowRejection::OutOfSeeds => write!(f, "Out of seeds - order more"),
+            SowRejection::QueueFull => write!(f, "Too many throws queued"),
+        }
+    }
+}
 
-            if can_sow {
-                bank_account.deduct(seed_type.price());
-                bank_account_update_events.write(BankAccountUpdateEvent);
-
-                throw_seed_events.write(ThrowSeedEvent {
-                    from_player: true,
-                    path: seed_path.unwrap(),
-                    seed_type,
-                });
-            } else {
-                // Play sound is cannot sow
-                commands.spawn((
-                    sound_effect(farm_assets.invalid_sound.clone()),
-                    Transform::from_translation(click_position.extend(0.)),
-                ));
-            }
+/// Sent when a click on the farm is refused, with where the seed would have gone.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct SowRejectedEvent {
+    pub rejection: SowRejection,
+    pub position: Vec2,
+}
+
+/// Where a seed thrown at a tile would fly, and what (if anything) stops it being thrown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThrowPlan {
+    /// The centre of the tile, where seeds always land.
+    pub target: Vec2,
+    /// The relay route from the farmer through gnomes, if the tile is in range.
+    pub path: Option<Vec<IVec2>>,
+    /// Why the seed can't be thrown, if it can't.
+    pub problem: Option<SowRejection>,
+}
+
+/// Work out whether a seed can be thrown at `tile`, and which way it would go.
+pub fn plan_throw(
+    tile: IVec2,
+    player_position: IVec2,
+    gnome_positions: Vec<IVec2>,
+    occupied: bool,
+    queue_full: bool,
+    in_stock: bool,
+) -> ThrowPlan {
+    let target = FarmGrid::tile_center(tile);
+    let path = throw_path(
+        player_position,
+        gnome_positions,
+        target.as_ivec2(),
+        PLAYER_THROW_RADIUS_PX,
+        GNOME_THROW_RADIUS_PX,
+    );
+
+    let problem = if queue_full {
+        Some(SowRejection::QueueFull)
+    } else if target.distance(player_position.as_vec2()) < PLAYER_THROW_MIN_DIST_PX {
+        Some(SowRejection::TooCloseToFarmer)
+    } else if path.is_none() {
+        Some(SowRejection::NoThrowPath)
+    } else if occupied {
+        Some(SowRejection::Occupied)
+    } else if !in_stock {
+        Some(SowRejection::OutOfSeeds)
+    } else {
+        None
+    };
+
+    ThrowPlan {
+        target,
+        path,
+        problem,
+    }
+}
+
+/// Where the grown gnomes stand, ready to relay seeds.
+pub fn gnome_positions(
+    q_grown_plants: &Query<(&Transform, &Plant), Without<GrowthTimer>>,
+) -> Vec<IVec2> {
+    q_grown_plants
+        .iter()
+        .filter(|(_, p)| p.plant_type() == PlantType::Gnome)
+        .map(|(t, _)| t.translation.xy().as_ivec2())
+        .collect()
+}
+
+fn pay_wave_bonuses(
+    mut wave_cleared_events: EventReader<WaveClearedEvent>,
+    mut q_bank_account: Query<&mut BankAccount>,
+    mut bank_account_update_events: EventWriter<BankAccountUpdateEvent>,
+    mut get_coin_events: EventWriter<GetCoinEvent>,
+    q_player: Query<&Transform, With<Player>>,
+    tick: Res<GameTick>,
+) {
+    for ev in wave_cleared_events.read() {
+        if ev.bonus <= 0. {
+            continue;
+        }
+        let Ok(mut bank_account) = q_bank_account.single_mut() else {
+            warn!("No bank account!");
+            return;
+        };
+        info!("Paying {} for clearing wave {}", ev.bonus, ev.wave_number);
+        let transaction = Transaction::new(
+            TransactionKind::WaveBonus,
+            ev.bonus,
+            format!("Cleared wave {}", ev.wave_number),
+            // A wave isn't an entity
+            Some(TransactionSource::new(
+                None,
+                format!("Wave {}", ev.wave_number),
+            )),
+            *tick,
+        );
+        bank_account.record(transaction.clone());
+        bank_account_update_events.write(BankAccountUpdateEvent(Some(transaction)));
+
+        if let Ok(player_transform) = q_player.single() {
+            get_coin_events.write(GetCoinEvent(player_transform.translation));
         }
     }
 }
 
-fn restart_game(
+pub(super) fn restart_game(
     mut commands: Commands,
     mut events: EventReader<RestartGameEvent>,
     mut q_entities: Query<Entity, With<DespawnOnRestart>>,
     mut q_bank_account: Query<&mut BankAccount>,
     mut ev_bank_account_update: EventWriter<BankAccountUpdateEvent>,
+    mut throw_queue: ResMut<ThrowQueue>,
 ) {
     for _ in events.read() {
         info!("Receive restart event");
-        q_bank_account.single_mut().unwrap().balance = STARTING_BALANCE;
+        throw_queue.0.clear();
+        q_bank_account
+            .single_mut()
+            .unwrap()
+            .restart(STARTING_BALANCE);
         ev_bank_account_update.write_default();
 
         for entity in q_entities.iter_mut() {
@@ -320,26 +713,52 @@ fn end_game(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     q_plants: Query<&Plant>,
+    q_daisy_chains: Query<(), With<DaisyChainParcel>>,
+    q_seeds: Query<&PlantType, With<Seed>>,
+    q_inventory: Query<&Inventory, With<Player>>,
+    q_orders: Query<&MailOrder>,
     q_bank_account: Query<&BankAccount>,
     q_despawn_restart: Query<Entity, (With<DespawnOnRestart>, Without<EndGameDisplay>)>,
+    q_end_game_display: Query<(), With<EndGameDisplay>>,
     farm_assets: Res<FarmAssets>,
 ) {
+    if !q_end_game_display.is_empty() {
+        // Already over
+        return;
+    }
     let Ok(bank_account) = q_bank_account.single() else {
         // No bank account
         return;
     };
     if bank_account.balance <= LOSING_BALANCE {
-        let daisy_count = q_plants
-            .iter()
-            .filter(|p| p.plant_type() == PlantType::Daisy)
-            .count();
-        if daisy_count >= DAISY_CHAIN_LENGTH {
+        // Plants still to be sown, or on their way by mail, count too
+        let inventory = q_inventory.single().ok();
+        let count = |plant_type: PlantType| {
+            q_plants
+                .iter()
+                .filter(|p| p.plant_type() == plant_type)
+                .count()
+                + inventory.map_or(0, |inventory| inventory.count(plant_type) as usize)
+                + q_seeds.iter().filter(|&&p| p == plant_type).count()
+                + q_orders
+                    .iter()
+                    .filter(|order| order.plant_type() == plant_type)
+                    .map(|order| order.quantity() as usize)
+                    .sum::<usize>()
+        };
+        // A chain only sells once a gnome relays it to the farmer
+        let can_sell_chain =
+            count(PlantType::Daisy) >= DAISY_CHAIN_LENGTH && count(PlantType::Gnome) > 0;
+        if can_sell_chain || !q_daisy_chains.is_empty() {
             // There's still a chance
             return;
         }
         commands.spawn(end_game_text(
             Name::new("GameOverText"),
-            "You ran out of money",
+            &format!(
+                "You ran out of money\n\n{}",
+                LedgerSummary::new(bank_account.transactions())
+            ),
             LOSER_BACKGROUND,
             asset_server,
             &farm_assets,
@@ -348,7 +767,10 @@ fn end_game(
     } else if bank_account.balance >= WINNING_BALANCE {
         commands.spawn(end_game_text(
             Name::new("WinGameText"),
-            "You earned enough money to buy chain cutters! You win!",
+            &format!(
+                "You earned enough money to buy chain cutters! You win!\n\n{}",
+                LedgerSummary::new(bank_account.transactions())
+            ),
             WINNER_BACKGROUND,
             asset_server,
             &farm_assets,
@@ -358,6 +780,11 @@ fn end_game(
         return;
     }
 
+    // The game's over, so there's nothing to pick up again
+    if let Err(e) = SaveGame::delete() {
+        warn!("{}", e);
+    }
+
     // Despawn everything and show a restart button
     for entity in q_despawn_restart {
         commands.entity(entity).despawn();
@@ -439,12 +866,116 @@ fn end_game_button_system(
             With<EndGameRestartButton>,
         ),
     >,
-    mut restart_events: EventWriter<RestartGameEvent>,
+    mut inputs: EventWriter<ReplayInput>,
 ) {
     for interaction in q_interactions {
         if *interaction == Interaction::Pressed {
             info!("Press endgame restart button");
-            restart_events.write_default();
+            inputs.write(ReplayInput::Restart);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::sim::Sim;
+
+    /// The centre of the tile containing (100, 0).
+    const TILE: Vec2 = Vec2::new(128., 64.);
+
+    #[test]
+    fn test_tile_center_round_trip() {
+        for x in 0..FARM_SIZE_TILES.x {
+            for y in 0..FARM_SIZE_TILES.y {
+                let tile = IVec2::new(x, y);
+                assert_eq!(FarmGrid::tile_at(FarmGrid::tile_center(tile)), Some(tile));
+            }
         }
     }
+
+    #[test]
+    fn test_tile_at_out_of_bounds() {
+        assert_eq!(FarmGrid::tile_at(FARM_SIZE_PX), None);
+        assert_eq!(FarmGrid::tile_at(-FARM_SIZE_PX), None);
+        assert_eq!(FarmGrid::tile_at(-FARM_SIZE_PX / 2.), Some(IVec2::ZERO));
+    }
+
+    #[test]
+    fn test_find_path_around_obstacle() {
+        let blocked: HashSet<IVec2> = [IVec2::new(1, 0), IVec2::new(1, 1)].into_iter().collect();
+        let path = FarmGrid::find_path(IVec2::new(0, 0), IVec2::new(2, 0), &blocked);
+        assert!(path.is_some());
+
+        let path = path.unwrap();
+        assert_eq!(path.first(), Some(&IVec2::new(0, 0)));
+        assert_eq!(path.last(), Some(&IVec2::new(2, 0)));
+        assert!(path.iter().all(|t| !blocked.contains(t)));
+        // Under the wall through the margin outside the farm: down, across 2, up
+        assert_eq!(path.len(), 5);
+    }
+
+    #[test]
+    fn test_find_path_into_blocked_goal() {
+        let blocked: HashSet<IVec2> = [IVec2::new(1, 0)].into_iter().collect();
+        let path = FarmGrid::find_path(IVec2::new(0, 0), IVec2::new(1, 0), &blocked);
+        assert_eq!(path, Some(vec![IVec2::new(0, 0), IVec2::new(1, 0)]));
+    }
+
+    #[test]
+    fn test_neighbours_at_corner() {
+        let neighbours: Vec<IVec2> = FarmGrid::neighbours(IVec2::ZERO).collect();
+        assert_eq!(neighbours.len(), 2);
+        assert!(neighbours.contains(&IVec2::new(1, 0)));
+        assert!(neighbours.contains(&IVec2::new(0, 1)));
+    }
+
+    #[test]
+    fn clicks_queue_up_while_the_farmer_throws() {
+        let mut sim = Sim::new();
+        sim.click(Vec2::new(100., 0.));
+        sim.click(Vec2::new(-100., -150.));
+        sim.step();
+        assert_eq!(sim.rejections(), vec![]);
+        sim.sown_at(TILE);
+        sim.sown_at(Vec2::new(-128., -192.));
+    }
+
+    #[test]
+    fn click_is_rejected_when_the_queue_is_full() {
+        let mut sim = Sim::new();
+        for _ in 0..ThrowLimits::default().max_queued {
+            sim.click(Vec2::new(100., 0.));
+        }
+        sim.step();
+        assert_eq!(sim.rejections(), vec![]);
+        // The first throw has left the queue, so this fills it up again
+        sim.click(Vec2::new(100., 0.));
+        sim.click(Vec2::new(100., 0.));
+        sim.step();
+        assert_eq!(sim.rejections(), vec![SowRejection::QueueFull]);
+    }
+
+    #[test]
+    fn broke_with_daisies_but_no_gnome_is_a_loss() {
+        let mut sim = Sim::new();
+        sim.set_stock(&[(PlantType::Daisy, DAISY_CHAIN_LENGTH as u32)]);
+        sim.set_balance(0.);
+        sim.step();
+        assert!(sim.lost());
+        sim.advance(0.5);
+        assert_eq!(sim.end_game_displays(), 1);
+    }
+
+    #[test]
+    fn broke_with_daisies_and_a_gnome_is_not_a_loss() {
+        let mut sim = Sim::new();
+        sim.set_stock(&[
+            (PlantType::Daisy, DAISY_CHAIN_LENGTH as u32),
+            (PlantType::Gnome, 1),
+        ]);
+        sim.set_balance(0.);
+        sim.step();
+        assert!(!sim.lost());
+    }
 }
@@ -0,0 +1,198 @@
+//! Burning plants take damage over time and set their neighbours alight.
+
+use crate::PausableSystems;
+use crate::game::farm::FarmGrid;
+use crate::game::health::Health;
+use crate::game::plant::Plant;
+use crate::game::plant_registry::PlantRegistry;
+use crate::game::smoke::SpawnSmokeEvent;
+use crate::theme::palette::BURNING_TINT;
+use bevy::prelude::*;
+
+const BURN_DURATION_S: f32 = 4.0;
+const BURN_TICK_S: f32 = 1.0;
+const BURN_DAMAGE: i32 = 1;
+/// How long a plant burns before the fire reaches the tiles next to it.
+const BURN_SPREAD_DELAY_S: f32 = 1.5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Burning>();
+
+    app.add_event::<IgnitePlantEvent>();
+
+    app.add_observer(tint_burning);
+    app.add_observer(untint_burning);
+
+    app.add_systems(
+        FixedUpdate,
+        (ignite_plants, tick_burning, spread_fire)
+            .chain()
+            .run_if(resource_exists::<PlantRegistry>)
+            .in_set(PausableSystems),
+    );
+}
+
+/// A plant that is on fire.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct Burning {
+    /// Time until the fire burns itself out.
+    remaining: Timer,
+    damage: Timer,
+    /// Time until the fire spreads. It only spreads once per plant.
+    spread: Timer,
+}
+
+impl Default for Burning {
+    fn default() -> Self {
+        Self {
+            remaining: Timer::from_seconds(BURN_DURATION_S, TimerMode::Once),
+            damage: Timer::from_seconds(BURN_TICK_S, TimerMode::Repeating),
+            spread: Timer::from_seconds(BURN_SPREAD_DELAY_S, TimerMode::Once),
+        }
+    }
+}
+
+impl Burning {
+    /// How long the plant has been burning.
+    pub fn elapsed_secs(&self) -> f32 {
+        self.remaining.elapsed_secs()
+    }
+
+    /// A fire that's been burning for `elapsed_s` already. Its timers are ticked rather than set,
+    /// so a fire that has already spread doesn't spread again.
+    pub fn burnt_for(elapsed_s: f32) -> Self {
+        let mut burning = Self::default();
+        let elapsed = std::time::Duration::from_secs_f32(elapsed_s);
+        burning.remaining.tick(elapsed);
+        burning.damage.tick(elapsed);
+        burning.spread.tick(elapsed);
+        burning
+    }
+}
+
+#[derive(Event, Debug)]
+pub struct IgnitePlantEvent {
+    pub plant_entity: Entity,
+}
+
+/// Set plants on fire, unless they're fire-immune or already burning.
+fn ignite_plants(
+    mut commands: Commands,
+    mut ignite_events: EventReader<IgnitePlantEvent>,
+    q_plants: Query<(&Plant, &Transform), Without<Burning>>,
+    mut spawn_smoke_events: EventWriter<SpawnSmokeEvent>,
+    plant_registry: Res<PlantRegistry>,
+) {
+    for ev in ignite_events.read() {
+        let Ok((plant, transform)) = q_plants.get(ev.plant_entity) else {
+            continue;
+        };
+        if plant_registry
+            .get(plant.plant_type())
+            .is_none_or(|p| p.fire_immune)
+        {
+            continue;
+        }
+        info!("Plant {:?} caught fire", ev.plant_entity);
+        commands
+            .entity(ev.plant_entity)
+            .try_insert(Burning::default());
+        spawn_smoke_events.write(SpawnSmokeEvent(transform.translation));
+    }
+}
+
+fn tick_burning(
+    mut commands: Commands,
+    mut q_burning: Query<(Entity, &mut Burning, &mut Health, &Transform)>,
+    mut spawn_smoke_events: EventWriter<SpawnSmokeEvent>,
+    time: Res<Time>,
+) {
+    for (entity, mut burning, mut health, transform) in &mut q_burning {
+        burning.remaining.tick(time.delta());
+        burning.spread.tick(time.delta());
+        burning.damage.tick(time.delta());
+
+        for _ in 0..burning.damage.times_finished_this_tick() {
+            health.reduce(BURN_DAMAGE);
+            spawn_smoke_events.write(SpawnSmokeEvent(transform.translation));
+        }
+
+        if burning.remaining.finished() {
+            info!("Fire on {:?} burnt out", entity);
+            commands.entity(entity).try_remove::<Burning>();
+        }
+    }
+}
+
+/// Once a plant has been burning for a while, ignite the plants on the tiles next to it.
+fn spread_fire(
+    q_burning: Query<(Entity, &Burning, &Transform)>,
+    farm_grid: Res<FarmGrid>,
+    mut ignite_events: EventWriter<IgnitePlantEvent>,
+) {
+    for (entity, burning, transform) in q_burning {
+        if !burning.spread.just_finished() {
+            continue;
+        }
+        let Some(tile) = FarmGrid::tile_at(transform.translation.xy()) else {
+            continue;
+        };
+        for neighbour in FarmGrid::neighbours(tile) {
+            if let Some(plant_entity) = farm_grid.occupant(neighbour) {
+                debug!("Fire spreads from {:?} to {:?}", entity, plant_entity);
+                ignite_events.write(IgnitePlantEvent { plant_entity });
+            }
+        }
+    }
+}
+
+fn tint_burning(trigger: Trigger<OnAdd, Burning>, mut q_sprites: Query<&mut Sprite>) {
+    if let Ok(mut sprite) = q_sprites.get_mut(trigger.target()) {
+        sprite.color = BURNING_TINT;
+    }
+}
+
+fn untint_burning(trigger: Trigger<OnRemove, Burning>, mut q_sprites: Query<&mut Sprite>) {
+    if let Ok(mut sprite) = q_sprites.get_mut(trigger.target()) {
+        sprite.color = Color::WHITE;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::plant::PlantType;
+    use crate::game::sim::Sim;
+
+    /// The centre of the tile containing (100, 0).
+    const TILE: Vec2 = Vec2::new(128., 64.);
+    const LEFT: Vec2 = Vec2::new(0., 64.);
+    const BELOW: Vec2 = Vec2::new(128., -64.);
+
+    #[test]
+    fn fire_spreads_to_neighbours_but_not_fire_immune_plants() {
+        let mut sim = Sim::new();
+        sim.set_stock(&[(PlantType::Daisy, 2), (PlantType::Dragonfruit, 1)]);
+        sim.click(Vec2::new(100., 0.));
+        sim.click(LEFT);
+        sim.sown_at(TILE);
+        sim.sown_at(LEFT);
+        sim.select_seed(PlantType::Dragonfruit);
+        sim.click(Vec2::new(100., -40.));
+        let dragonfruit = sim.sown_at(BELOW);
+
+        sim.ignite(BELOW);
+        sim.step();
+        assert!(!sim.plant_at(BELOW).unwrap().burning);
+
+        sim.ignite(TILE);
+        sim.advance(1.2);
+        assert!(sim.plant_at(TILE).unwrap().burning);
+        assert!(!sim.plant_at(LEFT).unwrap().burning);
+        sim.advance(0.5);
+        assert!(sim.plant_at(LEFT).unwrap().burning);
+        assert!(!sim.plant_at(BELOW).unwrap().burning);
+        assert_eq!(sim.plant_at(BELOW).unwrap().health, dragonfruit.health);
+    }
+}
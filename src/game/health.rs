@@ -1,7 +1,7 @@
-use crate::PausableSystems;
 use crate::asset_tracking::LoadResource;
 use crate::audio::sound_effect;
 use crate::theme::palette::{HEALTH_HIGH, HEALTH_LOW, HEALTH_MED, HEALTH_OUTLINE};
+use crate::{DrawSystems, PausableSystems};
 use bevy::prelude::*;
 use bevy_vector_shapes::painter::ShapePainter;
 use bevy_vector_shapes::prelude::RectPainter;
@@ -12,10 +12,10 @@ pub(super) fn plugin(app: &mut App) {
     app.register_type::<HealthAssets>();
     app.load_resource::<HealthAssets>();
 
-    app.add_systems(Update, draw_health);
+    app.add_systems(Update, draw_health.in_set(DrawSystems));
 
     app.add_systems(
-        Update,
+        FixedUpdate,
         remove_dead
             .run_if(resource_exists::<HealthAssets>)
             .in_set(PausableSystems),
@@ -53,6 +53,18 @@ impl Health {
         }
     }
 
+    /// The same maximum health, but with only `current` left.
+    pub fn with_current(self, current: i32) -> Self {
+        Self {
+            current: current.clamp(0, self.max),
+            ..self
+        }
+    }
+
+    pub fn current(&self) -> i32 {
+        self.current
+    }
+
     pub fn reduce(&mut self, amount: i32) {
         self.current = std::cmp::max(0, self.current - amount);
     }
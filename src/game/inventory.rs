@@ -0,0 +1,213 @@
+//! The seeds the farmer has to hand.
+//!
+//! Every throw takes a seed from the player's [`Inventory`]. It's filled by mail order deliveries,
+//! by the farmer picking up seeds that land where nothing can be sown, and by enemies dropping the
+//! seeds they snatched out of the air when they die, so a seed is never lost by a miss.
+
+use crate::game::enemy::Enemy;
+use crate::game::farm::RestartGameEvent;
+use crate::game::health::Health;
+use crate::game::lifespan::LifespanTimer;
+use crate::game::plant::PlantType;
+use crate::game::player::Player;
+use crate::game::seed::{SeedInterceptedEvent, SeedMissedEvent};
+use crate::screens::Screen;
+use crate::theme::palette::SEED_PICKUP;
+use crate::{AppSystems, PausableSystems};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+/// What's in the inventory at the start of a game.
+const STARTING_SEEDS: [(PlantType, u32); 1] = [(PlantType::Daisy, 3)];
+/// How far above a pickup its label floats.
+const PICKUP_LABEL_OFFSET_PX: f32 = 30.;
+const PICKUP_LABEL_SECS: f32 = 1.;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Inventory>();
+    app.register_type::<CarriedSeeds>();
+    app.add_observer(drop_carried_seeds);
+
+    app.add_systems(
+        FixedUpdate,
+        (
+            reset_inventory.run_if(on_event::<RestartGameEvent>),
+            carry_intercepted_seeds,
+            catch_missed_seeds,
+        )
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// The seeds a player has to hand, by species.
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Inventory {
+    seeds: HashMap<PlantType, u32>,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        let mut inventory = Self {
+            seeds: HashMap::default(),
+        };
+        for (plant_type, count) in STARTING_SEEDS {
+            inventory.add(plant_type, count);
+        }
+        inventory
+    }
+}
+
+impl Inventory {
+    pub fn count(&self, plant_type: PlantType) -> u32 {
+        self.seeds.get(&plant_type.species()).copied().unwrap_or(0)
+    }
+
+    pub fn add(&mut self, plant_type: PlantType, count: u32) {
+        *self.seeds.entry(plant_type.species()).or_default() += count;
+    }
+
+    /// Take out one seed, returning whether there was one to take.
+    pub fn take(&mut self, plant_type: PlantType) -> bool {
+        match self.seeds.get_mut(&plant_type.species()) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn save(&self) -> Vec<(PlantType, u32)> {
+        self.seeds.iter().map(|(p, c)| (*p, *c)).collect()
+    }
+
+    pub fn load(saved: &[(PlantType, u32)]) -> Self {
+        Self {
+            seeds: saved.iter().copied().collect(),
+        }
+    }
+}
+
+/// Seeds an enemy has caught, which it drops if it's killed.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct CarriedSeeds(pub Vec<PlantType>);
+
+fn reset_inventory(mut q_inventory: Query<&mut Inventory, With<Player>>) {
+    for mut inventory in q_inventory.iter_mut() {
+        *inventory = Inventory::default();
+    }
+}
+
+/// Show what was picked up where it was picked up.
+fn pickup_label(text: String, position: Vec2) -> impl Bundle {
+    (
+        Name::new("Pickup Label"),
+        Text2d::new(text),
+        TextColor(SEED_PICKUP),
+        Transform::from_translation((position + Vec2::Y * PICKUP_LABEL_OFFSET_PX).extend(2.)),
+        LifespanTimer(Timer::from_seconds(PICKUP_LABEL_SECS, TimerMode::Once)),
+        StateScoped(Screen::Gameplay),
+    )
+}
+
+fn carry_intercepted_seeds(
+    mut commands: Commands,
+    mut seed_intercepted_events: EventReader<SeedInterceptedEvent>,
+    mut q_carried: Query<&mut CarriedSeeds, With<Enemy>>,
+) {
+    for ev in seed_intercepted_events.read() {
+        match q_carried.get_mut(ev.enemy) {
+            Ok(mut carried) => carried.0.push(ev.seed_type),
+            Err(_) => {
+                if let Ok(mut enemy) = commands.get_entity(ev.enemy) {
+                    enemy.insert(CarriedSeeds(vec![ev.seed_type]));
+                }
+            }
+        }
+    }
+}
+
+/// When an enemy is killed, the seeds it caught go back to the farmer. Enemies that wander off or
+/// are cleared away on restart take theirs with them.
+fn drop_carried_seeds(
+    trigger: Trigger<OnRemove, CarriedSeeds>,
+    mut commands: Commands,
+    q_enemies: Query<(&CarriedSeeds, &Health, &Transform)>,
+    mut q_inventory: Query<&mut Inventory, With<Player>>,
+) {
+    let Ok((carried, health, transform)) = q_enemies.get(trigger.target()) else {
+        return;
+    };
+    if health.current() > 0 || carried.0.is_empty() {
+        return;
+    }
+    let Ok(mut inventory) = q_inventory.single_mut() else {
+        return;
+    };
+    for &seed_type in &carried.0 {
+        info!("{:?} dropped a {:?} seed", trigger.target(), seed_type);
+        inventory.add(seed_type, 1);
+    }
+    commands.spawn(pickup_label(
+        format!("+{} seeds", carried.0.len()),
+        transform.translation.xy(),
+    ));
+}
+
+/// Seeds that land without being sown are picked up by the farmer and go back into the inventory.
+fn catch_missed_seeds(
+    mut commands: Commands,
+    mut seed_missed_events: EventReader<SeedMissedEvent>,
+    mut q_inventory: Query<&mut Inventory, With<Player>>,
+) {
+    let Ok(mut inventory) = q_inventory.single_mut() else {
+        return;
+    };
+    for ev in seed_missed_events.read() {
+        info!("Picked up a {:?} seed at {:?}", ev.seed_type, ev.position);
+        inventory.add(ev.seed_type, 1);
+        commands.spawn(pickup_label(
+            format!("+1 {}", ev.seed_type.species()),
+            ev.position,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::sim::Sim;
+
+    const TILE: Vec2 = Vec2::new(128., 64.);
+
+    #[test]
+    fn inventory_counts_seeds_by_species() {
+        let mut inventory = Inventory::load(&[]);
+        inventory.add(PlantType::Pineapple(2), 2);
+        assert_eq!(inventory.count(PlantType::Pineapple(0)), 2);
+        assert!(inventory.take(PlantType::Pineapple(1)));
+        assert!(inventory.take(PlantType::Pineapple(0)));
+        assert!(!inventory.take(PlantType::Pineapple(0)));
+        assert_eq!(inventory.count(PlantType::Daisy), 0);
+    }
+
+    #[test]
+    fn seed_landing_on_an_occupied_tile_goes_back_into_the_inventory() {
+        let mut sim = Sim::new();
+        let daisies = sim.stock(PlantType::Daisy);
+        sim.click(TILE);
+        sim.advance_until("the daisy seed is thrown", |sim| {
+            sim.stock(PlantType::Daisy) < daisies
+        });
+
+        // Something else takes the tile while the seed is in the air
+        sim.sow(PlantType::Gnome, TILE);
+        sim.advance_until("the daisy seed is picked up", |sim| {
+            sim.stock(PlantType::Daisy) == daisies
+        });
+        assert_eq!(sim.plant_at(TILE).unwrap().plant_type, PlantType::Gnome);
+    }
+}
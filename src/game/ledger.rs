@@ -0,0 +1,254 @@
+//! The bank account's ledger.
+//!
+//! Every change to the balance is a [`Transaction`], saying how much changed, why, what caused it
+//! and when. The pause menu lists the latest ones, and the end of the game sums them up.
+
+use crate::game::farm::BankAccount;
+use crate::game::replay::GameTick;
+use crate::menus::Menu;
+use crate::theme::palette::{HEADER_TEXT, LABEL_TEXT, LEDGER_EXPENSE, LEDGER_INCOME};
+use bevy::prelude::*;
+use bevy::ui::Val::*;
+use serde::{Deserialize, Serialize};
+
+/// How many transactions the pause menu lists.
+const LEDGER_PANEL_ROWS: usize = 10;
+
+/// What a transaction was for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum TransactionKind {
+    SeedPurchase,
+    DaisyChainSale,
+    WaveBonus,
+    Refund,
+}
+
+impl std::fmt::Display for TransactionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransactionKind::SeedPurchase => write!(f, "Seed purchase"),
+            TransactionKind::DaisyChainSale => write!(f, "Daisy chain sale"),
+            TransactionKind::WaveBonus => write!(f, "Wave bonus"),
+            TransactionKind::Refund => write!(f, "Refund"),
+        }
+    }
+}
+
+/// A single change to the bank balance.
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct Transaction {
+    pub kind: TransactionKind,
+    /// Positive for money coming in, negative for money going out.
+    pub amount: f32,
+    pub reason: String,
+    /// What the money was paid for or came from, if anything.
+    pub source: Option<TransactionSource>,
+    /// The tick of the game it happened in.
+    pub tick: GameTick,
+}
+
+impl Transaction {
+    pub fn new(
+        kind: TransactionKind,
+        amount: f32,
+        reason: impl Into<String>,
+        source: Option<TransactionSource>,
+        tick: GameTick,
+    ) -> Self {
+        Self {
+            kind,
+            amount,
+            reason: reason.into(),
+            source,
+            tick,
+        }
+    }
+
+    /// The transaction as it's saved, without the entity behind it.
+    pub fn saved(&self) -> Self {
+        let mut saved = self.clone();
+        if let Some(source) = &mut saved.source {
+            source.entity = None;
+        }
+        saved
+    }
+}
+
+/// What a transaction was paid for or came from.
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct TransactionSource {
+    /// The entity behind it, such as the order or the daisy chain, if there was one. It's
+    /// usually gone soon after, and entities don't survive a save, so it isn't saved.
+    #[serde(skip)]
+    pub entity: Option<Entity>,
+    /// What it was, such as a species of seed or a wave. This outlives the entity.
+    pub description: String,
+}
+
+impl TransactionSource {
+    pub fn new(entity: Option<Entity>, description: impl Into<String>) -> Self {
+        Self {
+            entity,
+            description: description.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for Transaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let seconds = self.tick.elapsed().as_secs();
+        let sign = if self.amount < 0. { '-' } else { '+' };
+        write!(
+            f,
+            "{:02}:{:02}  {}${:.2}  {}",
+            seconds / 60,
+            seconds % 60,
+            sign,
+            self.amount.abs(),
+            self.reason
+        )
+    }
+}
+
+/// Money in and out over a game, by kind of transaction.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LedgerSummary {
+    /// Totals for each kind, in the order they first happened.
+    totals: Vec<(TransactionKind, f32)>,
+}
+
+impl LedgerSummary {
+    pub fn new(transactions: &[Transaction]) -> Self {
+        let mut summary = Self::default();
+        for transaction in transactions {
+            match summary
+                .totals
+                .iter_mut()
+                .find(|(kind, _)| *kind == transaction.kind)
+            {
+                Some((_, total)) => *total += transaction.amount,
+                None => summary.totals.push((transaction.kind, transaction.amount)),
+            }
+        }
+        summary
+    }
+
+    pub fn income(&self) -> f32 {
+        self.totals.iter().map(|(_, t)| t.max(0.)).sum()
+    }
+
+    pub fn expenses(&self) -> f32 {
+        self.totals.iter().map(|(_, t)| (-t).max(0.)).sum()
+    }
+}
+
+impl std::fmt::Display for LedgerSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Income: ${:.2}", self.income())?;
+        for (kind, total) in self.totals.iter().filter(|(_, t)| *t > 0.) {
+            writeln!(f, "  {}: ${:.2}", kind, total)?;
+        }
+        writeln!(f, "Expenses: ${:.2}", self.expenses())?;
+        for (kind, total) in self.totals.iter().filter(|(_, t)| *t < 0.) {
+            writeln!(f, "  {}: ${:.2}", kind, -total)?;
+        }
+        Ok(())
+    }
+}
+
+/// List the latest transactions beside the pause menu.
+pub fn spawn_ledger_panel(mut commands: Commands, q_bank_account: Query<&BankAccount>) {
+    let Ok(bank_account) = q_bank_account.single() else {
+        return;
+    };
+    let transactions = bank_account.transactions();
+    let summary = LedgerSummary::new(transactions);
+
+    let mut panel = commands.spawn((
+        Name::new("Ledger Panel"),
+        Node {
+            position_type: PositionType::Absolute,
+            right: Px(20.0),
+            top: Px(20.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Px(4.0),
+            ..default()
+        },
+        GlobalZIndex(2),
+        Pickable::IGNORE,
+        StateScoped(Menu::Pause),
+    ));
+    panel.with_children(|parent| {
+        parent.spawn(ledger_text("Ledger", 28.0, HEADER_TEXT));
+        if transactions.is_empty() {
+            parent.spawn(ledger_text("No transactions yet", 16.0, LABEL_TEXT));
+        }
+        for transaction in transactions.iter().rev().take(LEDGER_PANEL_ROWS) {
+            let color = if transaction.amount < 0. {
+                LEDGER_EXPENSE
+            } else {
+                LEDGER_INCOME
+            };
+            parent.spawn(ledger_text(transaction.to_string(), 16.0, color));
+        }
+        parent.spawn(ledger_text(
+            format!(
+                "Income ${:.2}  Expenses ${:.2}",
+                summary.income(),
+                summary.expenses()
+            ),
+            18.0,
+            LABEL_TEXT,
+        ));
+    });
+}
+
+fn ledger_text(text: impl Into<String>, font_size: f32, color: Color) -> impl Bundle {
+    (
+        Name::new("Ledger Text"),
+        Text(text.into()),
+        TextFont::from_font_size(font_size),
+        TextColor(color),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::sim::Sim;
+
+    fn transaction(kind: TransactionKind, amount: f32) -> Transaction {
+        Transaction::new(kind, amount, "", None, GameTick::default())
+    }
+
+    #[test]
+    fn summary_totals_income_and_expenses_by_kind() {
+        let summary = LedgerSummary::new(&[
+            transaction(TransactionKind::SeedPurchase, -3.),
+            transaction(TransactionKind::DaisyChainSale, 10.),
+            transaction(TransactionKind::SeedPurchase, -2.),
+            transaction(TransactionKind::WaveBonus, 5.),
+        ]);
+        assert_eq!(summary.income(), 15.);
+        assert_eq!(summary.expenses(), 5.);
+        assert_eq!(
+            summary.to_string(),
+            "Income: $15.00\n  Daisy chain sale: $10.00\n  Wave bonus: $5.00\n\
+             Expenses: $5.00\n  Seed purchase: $5.00\n"
+        );
+    }
+
+    #[test]
+    fn orders_are_recorded_in_the_ledger() {
+        let mut sim = Sim::new();
+        sim.order(PlantType::Daisy, 1, true);
+        sim.step();
+        let transactions = sim.transactions();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].kind, TransactionKind::SeedPurchase);
+        assert_eq!(transactions[0].amount, -1.5);
+        let source = transactions[0].source.as_ref().unwrap();
+        assert_eq!(source.description, "Daisy");
+        assert!(source.entity.is_some());
+    }
+}
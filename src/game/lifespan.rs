@@ -4,7 +4,7 @@ use bevy::prelude::*;
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<LifespanTimer>();
 
-    app.add_systems(Update, tick_lifespans.in_set(PausableSystems));
+    app.add_systems(FixedUpdate, tick_lifespans.in_set(PausableSystems));
 }
 
 #[derive(Component, Debug, Clone, PartialEq, Eq, Default, Reflect)]
@@ -0,0 +1,378 @@
+//! Buying seeds by mail order.
+//!
+//! Seeds are paid for up front and turn up as a package at the barn's mailbox after a delivery
+//! delay, going into the farmer's [`Inventory`]. Buying in bulk is cheaper per seed, and express
+//! delivery is quicker but costs extra.
+
+use crate::asset_tracking::LoadResource;
+use crate::game::barn::Barn;
+use crate::game::despawn::DespawnOnRestart;
+use crate::game::farm::{BankAccount, BankAccountUpdateEvent};
+use crate::game::inventory::Inventory;
+use crate::game::ledger::{Transaction, TransactionKind, TransactionSource};
+use crate::game::lifespan::LifespanTimer;
+use crate::game::plant::PlantType;
+use crate::game::plant_registry::PlantRegistry;
+use crate::game::player::Player;
+use crate::game::replay::GameTick;
+use crate::screens::Screen;
+use crate::theme::palette::THROW_PREVIEW_INVALID;
+use crate::{AppSystems, PausableSystems};
+use bevy::image::{ImageLoaderSettings, ImageSampler};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The smallest order that gets the bulk discount.
+pub const BULK_ORDER_QUANTITY: u32 = 5;
+/// The fraction of the price taken off bulk orders.
+const BULK_DISCOUNT: f32 = 0.2;
+/// How much more express delivery costs, as a fraction of the price.
+const EXPRESS_MARKUP: f32 = 0.5;
+const DELIVERY_S: f32 = 8.;
+const EXPRESS_DELIVERY_S: f32 = 2.;
+/// Where packages are left, relative to the barn.
+const MAILBOX_OFFSET: Vec2 = Vec2::new(56., -40.);
+const PACKAGE_Z_LAYER: f32 = 1.5;
+const PACKAGE_SCALE: f32 = 0.5;
+const PACKAGE_LIFESPAN_S: f32 = 1.5;
+/// How far above the mailbox the reason for a refused order floats.
+const REJECTION_LABEL_OFFSET_PX: f32 = 40.;
+/// How long the reason for a refused order stays up.
+const REJECTION_TOAST_SECS: f32 = 1.5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<MailOrder>();
+    app.add_event::<OrderSeedsEvent>();
+    app.add_event::<OrderRejectedEvent>();
+
+    app.register_type::<MailOrderAssets>();
+    app.load_resource::<MailOrderAssets>();
+
+    app.add_systems(
+        FixedUpdate,
+        (place_orders, deliver_orders)
+            .chain()
+            .run_if(resource_exists::<MailOrderAssets>.and(resource_exists::<PlantRegistry>))
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        Update,
+        toast_rejected_orders
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update),
+    );
+}
+
+/// Buy `quantity` seeds of a plant, to be delivered to the mailbox.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct OrderSeedsEvent {
+    pub plant_type: PlantType,
+    pub quantity: u32,
+    pub express: bool,
+}
+
+/// Sent when an order is refused because it costs more than the bank balance.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct OrderRejectedEvent {
+    pub order: OrderSeedsEvent,
+    pub price: f32,
+    /// The mailbox, where the order would have been delivered.
+    pub position: Vec2,
+}
+
+/// What an order costs, given the price of a single seed.
+pub fn order_price(unit_price: f32, quantity: u32, express: bool) -> f32 {
+    let mut price = unit_price * quantity as f32;
+    if quantity >= BULK_ORDER_QUANTITY {
+        price *= 1. - BULK_DISCOUNT;
+    }
+    if express {
+        price *= 1. + EXPRESS_MARKUP;
+    }
+    price
+}
+
+/// An order that's been paid for and is on its way.
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct MailOrder {
+    plant_type: PlantType,
+    quantity: u32,
+    /// What the order cost, which is refunded if it can't be delivered.
+    paid: f32,
+    delivery: Timer,
+}
+
+impl MailOrder {
+    fn new(plant_type: PlantType, quantity: u32, paid: f32, delivery_s: f32) -> Self {
+        Self {
+            plant_type: plant_type.species(),
+            quantity,
+            paid,
+            delivery: Timer::from_seconds(delivery_s, TimerMode::Once),
+        }
+    }
+
+    pub fn plant_type(&self) -> PlantType {
+        self.plant_type
+    }
+
+    pub fn quantity(&self) -> u32 {
+        self.quantity
+    }
+
+    /// How many seconds until the order arrives.
+    pub fn remaining_secs(&self) -> f32 {
+        self.delivery.remaining_secs()
+    }
+}
+
+fn mail_order(order: MailOrder) -> impl Bundle {
+    (
+        Name::new(format!(
+            "Mail order ({} {})",
+            order.quantity, order.plant_type
+        )),
+        order,
+        DespawnOnRestart,
+    )
+}
+
+/// An order on its way as it was when the game was saved.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SavedMailOrder {
+    plant_type: PlantType,
+    quantity: u32,
+    paid: f32,
+    remaining_s: f32,
+}
+
+impl SavedMailOrder {
+    pub fn new(order: &MailOrder) -> Self {
+        Self {
+            plant_type: order.plant_type,
+            quantity: order.quantity,
+            paid: order.paid,
+            remaining_s: order.remaining_secs(),
+        }
+    }
+
+    pub fn spawn(&self, commands: &mut Commands) {
+        commands.spawn(mail_order(MailOrder::new(
+            self.plant_type,
+            self.quantity,
+            self.paid,
+            self.remaining_s,
+        )));
+    }
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct MailOrderAssets {
+    #[dependency]
+    package: Handle<Image>,
+}
+
+impl FromWorld for MailOrderAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            package: assets.load_with_settings(
+                "images/box.png",
+                |settings: &mut ImageLoaderSettings| {
+                    // Use `nearest` image sampling to preserve pixel art style.
+                    settings.sampler = ImageSampler::nearest();
+                },
+            ),
+        }
+    }
+}
+
+/// Where packages turn up.
+fn mailbox_position(q_barn: &Query<&Transform, With<Barn>>) -> Vec2 {
+    q_barn.single().map_or(Vec2::ZERO, |t| t.translation.xy()) + MAILBOX_OFFSET
+}
+
+fn place_orders(
+    mut commands: Commands,
+    mut order_events: EventReader<OrderSeedsEvent>,
+    mut q_bank_account: Query<&mut BankAccount>,
+    mut bank_account_update_events: EventWriter<BankAccountUpdateEvent>,
+    mut order_rejected_events: EventWriter<OrderRejectedEvent>,
+    q_barn: Query<&Transform, With<Barn>>,
+    plant_registry: Res<PlantRegistry>,
+    tick: Res<GameTick>,
+) {
+    for ev in order_events.read() {
+        let Ok(mut bank_account) = q_bank_account.single_mut() else {
+            warn!("No bank account!");
+            return;
+        };
+        let price = order_price(plant_registry.price(ev.plant_type), ev.quantity, ev.express);
+        if bank_account.balance() < price {
+            info!(
+                "Can't afford {} {} for {}. We have {}",
+                ev.quantity,
+                ev.plant_type,
+                price,
+                bank_account.balance()
+            );
+            order_rejected_events.write(OrderRejectedEvent {
+                order: *ev,
+                price,
+                position: mailbox_position(&q_barn),
+            });
+            continue;
+        }
+
+        let (delivery_s, delivery) = if ev.express {
+            (EXPRESS_DELIVERY_S, " by express")
+        } else {
+            (DELIVERY_S, "")
+        };
+        let order = commands
+            .spawn(mail_order(MailOrder::new(
+                ev.plant_type,
+                ev.quantity,
+                price,
+                delivery_s,
+            )))
+            .id();
+        let transaction = Transaction::new(
+            TransactionKind::SeedPurchase,
+            -price,
+            format!("Ordered {} {}{}", ev.quantity, ev.plant_type, delivery),
+            Some(TransactionSource::new(
+                Some(order),
+                ev.plant_type.species().to_string(),
+            )),
+            *tick,
+        );
+        bank_account.record(transaction.clone());
+        bank_account_update_events.write(BankAccountUpdateEvent(Some(transaction)));
+    }
+}
+
+fn deliver_orders(
+    mut commands: Commands,
+    mut q_orders: Query<(Entity, &mut MailOrder)>,
+    mut q_inventory: Query<&mut Inventory, With<Player>>,
+    mut q_bank_account: Query<&mut BankAccount>,
+    mut bank_account_update_events: EventWriter<BankAccountUpdateEvent>,
+    q_barn: Query<&Transform, With<Barn>>,
+    mail_order_assets: Res<MailOrderAssets>,
+    time: Res<Time>,
+    tick: Res<GameTick>,
+) {
+    for (entity, mut order) in q_orders.iter_mut() {
+        order.delivery.tick(time.delta());
+        if !order.delivery.finished() {
+            continue;
+        }
+        commands.entity(entity).despawn();
+
+        // With no farmer to take the package, the order goes back and is paid back
+        let Ok(mut inventory) = q_inventory.single_mut() else {
+            let Ok(mut bank_account) = q_bank_account.single_mut() else {
+                continue;
+            };
+            let transaction = Transaction::new(
+                TransactionKind::Refund,
+                order.paid,
+                format!("Undelivered {} {}", order.quantity, order.plant_type),
+                Some(TransactionSource::new(
+                    Some(entity),
+                    order.plant_type.to_string(),
+                )),
+                *tick,
+            );
+            bank_account.record(transaction.clone());
+            bank_account_update_events.write(BankAccountUpdateEvent(Some(transaction)));
+            continue;
+        };
+
+        info!("Delivered {} {}", order.quantity, order.plant_type);
+        inventory.add(order.plant_type, order.quantity);
+        commands.spawn((
+            Name::new("Package"),
+            DespawnOnRestart,
+            Sprite {
+                image: mail_order_assets.package.clone(),
+                ..default()
+            },
+            Transform::from_translation(mailbox_position(&q_barn).extend(PACKAGE_Z_LAYER))
+                .with_scale(Vec3::splat(PACKAGE_SCALE)),
+            LifespanTimer(Timer::from_seconds(PACKAGE_LIFESPAN_S, TimerMode::Once)),
+        ));
+    }
+}
+
+fn toast_rejected_orders(
+    mut commands: Commands,
+    mut order_rejected_events: EventReader<OrderRejectedEvent>,
+) {
+    for ev in order_rejected_events.read() {
+        commands.spawn((
+            Name::new("Order Rejection Toast"),
+            Text2d::new("Can't afford this order"),
+            TextColor(THROW_PREVIEW_INVALID),
+            Transform::from_translation(
+                (ev.position + Vec2::Y * REJECTION_LABEL_OFFSET_PX).extend(2.),
+            ),
+            LifespanTimer(Timer::from_seconds(REJECTION_TOAST_SECS, TimerMode::Once)),
+            StateScoped(Screen::Gameplay),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::sim::Sim;
+
+    #[test]
+    fn bulk_orders_are_discounted_and_express_costs_extra() {
+        assert_eq!(order_price(2., 1, false), 2.);
+        assert_eq!(order_price(2., BULK_ORDER_QUANTITY, false), 8.);
+        assert_eq!(order_price(2., 1, true), 3.);
+    }
+
+    #[test]
+    fn ordered_seeds_arrive_after_the_delivery_delay() {
+        let mut sim = Sim::new();
+        sim.order(PlantType::Pineapple(0), 1, false);
+        sim.advance(7.5);
+        assert_eq!(sim.stock(PlantType::Pineapple(0)), 0);
+        sim.advance(1.);
+        assert_eq!(sim.stock(PlantType::Pineapple(0)), 1);
+    }
+
+    #[test]
+    fn unaffordable_orders_are_refused_without_charging() {
+        let mut sim = Sim::new();
+        sim.set_balance(1.);
+        sim.order(PlantType::Daisy, 1, true);
+        sim.advance_until("the order is refused", |sim| {
+            !sim.order_rejections().is_empty()
+        });
+        assert_eq!(sim.order_rejections(), vec![(PlantType::Daisy, 1)]);
+        assert!(sim.rejections().is_empty());
+        assert_eq!(sim.balance(), 1.);
+    }
+
+    #[test]
+    fn spending_the_last_dollar_on_daisies_is_not_a_loss() {
+        let mut sim = Sim::new();
+        sim.set_stock(&[]);
+        // $8 for ten in bulk, then $2 for two more
+        sim.order(PlantType::Daisy, 10, false);
+        sim.order(PlantType::Daisy, 2, false);
+        sim.advance(1.);
+        assert!(!sim.lost());
+        sim.advance(8.);
+        assert_eq!(sim.stock(PlantType::Daisy), 12);
+        assert!(!sim.lost());
+    }
+}
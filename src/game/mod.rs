@@ -1,33 +1,59 @@
 mod barn;
+mod camera;
 mod coin;
+mod daisy_chain;
 mod despawn;
 mod enemy;
+mod enemy_registry;
 mod farm;
+mod fire;
 mod health;
+mod inventory;
+pub mod ledger;
 pub mod level;
 mod lifespan;
+mod mail_order;
 mod physics;
 mod plant;
+mod plant_registry;
 pub mod player;
 mod player_animation;
+mod replay;
+pub mod rng;
+pub mod save;
 mod seed;
+#[cfg(test)]
+mod sim;
 mod smoke;
+mod speed;
+mod throw_network;
+mod throw_preview;
 pub mod ui;
+mod water;
+mod wave;
 
 use crate::game::player::{PlayerClickEvent, ThrowSeedEvent};
-use avian2d::PhysicsPlugins;
+use avian2d::prelude::{PhysicsInterpolationPlugin, PhysicsPlugins};
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
+    simulation_plugin(app);
+    app.add_plugins((camera::plugin, throw_preview::plugin, ui::plugin));
+}
+
+/// Everything in the game except its UI, which is all a headless simulation needs.
+fn simulation_plugin(app: &mut App) {
     app.add_event::<PlayerClickEvent>();
     app.add_event::<ThrowSeedEvent>();
 
-    app.add_plugins(PhysicsPlugins::default());
+    // Bodies move on the fixed timestep, so smooth them out between ticks for rendering
+    app.add_plugins(PhysicsPlugins::default().set(PhysicsInterpolationPlugin::interpolate_all()));
 
     app.add_plugins((
         health::plugin,
         lifespan::plugin,
         plant::plugin,
+        plant_registry::plugin,
         enemy::plugin,
         coin::plugin,
         farm::plugin,
@@ -36,7 +62,20 @@ pub(super) fn plugin(app: &mut App) {
         player_animation::plugin,
         seed::plugin,
         smoke::plugin,
-        ui::plugin,
         barn::plugin,
+        enemy_registry::plugin,
+    ));
+    app.add_plugins((
+        daisy_chain::plugin,
+        fire::plugin,
+        inventory::plugin,
+        mail_order::plugin,
+        replay::plugin,
+        rng::plugin,
+        save::plugin,
+        speed::plugin,
+        throw_network::plugin,
+        water::plugin,
+        wave::plugin,
     ));
 }
@@ -1,16 +1,20 @@
-use crate::PausableSystems;
 use crate::asset_tracking::LoadResource;
 use crate::audio::sound_effect;
-use crate::game::coin::GetCoinEvent;
 use crate::game::despawn::DespawnOnRestart;
-use crate::game::farm::{BankAccount, BankAccountUpdateEvent};
+use crate::game::farm::{EnemyObstacle, FarmGrid, TileOccupant};
+use crate::game::fire::{Burning, IgnitePlantEvent};
 use crate::game::health::Health;
 use crate::game::lifespan::LifespanTimer;
 use crate::game::physics::GameLayer;
+use crate::game::plant_registry::{PlantDefinition, PlantRegistry};
+use crate::game::rng::GameRng;
 use crate::game::smoke::SpawnSmokeEvent;
+use crate::game::water::Hydration;
 use crate::theme::palette::{
-    GNOME_THROW_OUTLINE, PLANT_GROWTH_BAR_OUTLINE, PLANT_GROWTH_FOREGROUND, PLANT_OUTLINE,
+    BURNING_TINT, PLANT_GROWTH_BAR_OUTLINE, PLANT_GROWTH_FOREGROUND, PLANT_GROWTH_THIRSTY,
+    PLANT_OUTLINE,
 };
+use crate::{DrawSystems, PausableSystems, Pause};
 use avian2d::prelude::{
     Collider, CollisionEventsEnabled, CollisionLayers, CollisionStarted, LinearVelocity, RigidBody,
 };
@@ -20,24 +24,15 @@ use bevy_cobweb::prelude::*;
 use bevy_vector_shapes::painter::ShapePainter;
 use bevy_vector_shapes::prelude::*;
 use rand::prelude::SliceRandom;
+use serde::{Deserialize, Serialize};
 use std::fmt::Formatter;
+use std::time::Duration;
 
 pub const GNOME_THROW_RADIUS_PX: f32 = 500.;
-const DAISY_GROWTH_TIME_S: f32 = 3.;
 
-pub const DAISY_CHAIN_LENGTH: usize = 3;
-const DAISY_CHAIN_VALUE: f32 = 10.;
-
-pub const GNOME_STRENGTH: i32 = 1;
-pub const PINEAPPLE_STRENGTH: i32 = 2;
-pub const DRAGONFRUIT_STRENGTH: i32 = 1;
-
-pub const PINEAPPLE_SPREAD_DISTANCE: f32 = 45.;
-const PINEAPPLE_HEALTH_CURVE: [i32; 3] = [5, 3, 1];
-const PINEAPPLE_RADIUS_CURVE: [f32; 3] = [60., 45., 25.];
-const PINEAPPLE_SCALE_CURVE: [f32; 3] = [64., 48., 32.];
 pub const PINEAPPLE_DEFAULT_GENERATION: i32 = 0;
-pub const PINEAPPLE_MAX_GENERATION: i32 = PINEAPPLE_HEALTH_CURVE.len() as i32 - 1;
+
+const GROWN_SCALE: f32 = 0.5;
 
 const FIREBALL_RADIUS_PX: f32 = 30.;
 const FIREBALL_START_OFFSET_PX: f32 = 40.;
@@ -45,12 +40,6 @@ const FIREBALL_LIFESPAN_S: f32 = 1.0;
 const FIREBALL_MOVE_SPEED: f32 = 15.0;
 const FIREBALL_DAMAGE: i32 = 2;
 
-// Prices are set both here and in the .cobweb file
-const DAISY_PRICE: f32 = 1.;
-const PINEAPPLE_PRICE: f32 = 2.;
-const DRAGONFRUIT_PRICE: f32 = 3.;
-const GNOME_PRICE: f32 = 5.;
-
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<Plant>();
 
@@ -60,68 +49,61 @@ pub(super) fn plugin(app: &mut App) {
     app.add_event::<SowPlantEvent>();
     app.add_event::<DamagePlantEvent>();
     app.add_event::<SpewFireEvent>();
-    app.add_event::<SellDaisyChainEvent>();
+    app.add_event::<SelectSeedEvent>();
 
     app.add_systems(
-        Update,
+        FixedUpdate,
         (
             sow_plants,
             damage_plants,
             tick_growth,
             spew_fire,
             burn_stuff,
-            form_daisy_chains,
-            sell_daisy_chains,
         )
-            .run_if(resource_exists::<PlantAssets>)
+            .run_if(resource_exists::<PlantAssets>.and(resource_exists::<PlantRegistry>))
             .in_set(PausableSystems),
     );
+    app.add_systems(FixedUpdate, select_seed.in_set(PausableSystems));
     app.add_systems(
         Update,
-        (draw_plant_circles, draw_growth, draw_gnome_throw_circles),
+        (draw_plant_circles, draw_growth)
+            .run_if(resource_exists::<PlantRegistry>)
+            .in_set(DrawSystems),
     );
 }
 
-fn plant(position: Vec2, plant_assets: &PlantAssets, plant_type: PlantType) -> impl Bundle {
+fn plant(
+    position: Vec2,
+    plant_assets: &PlantAssets,
+    plant_registry: &PlantRegistry,
+    definition: &PlantDefinition,
+    plant_type: PlantType,
+) -> impl Bundle {
     (
         Name::new(format!("Plant at {:?}", position)),
         Plant { plant_type },
+        TileOccupant,
         RigidBody::Static,
         DespawnOnRestart,
-        Collider::circle(plant_radius(plant_type)),
-        CollisionLayers::new([GameLayer::Plant], [GameLayer::Plant, GameLayer::Enemy]),
+        Collider::circle(plant_registry.radius(plant_type)),
+        CollisionLayers::new(
+            [GameLayer::Plant],
+            [GameLayer::Plant, GameLayer::Enemy, GameLayer::Fireball],
+        ),
         Sprite {
             image: plant_assets.seedling.clone(),
             ..default()
         },
-        GrowthTimer(Timer::from_seconds(DAISY_GROWTH_TIME_S, TimerMode::Once)),
-        Health::new(plant_max_health(plant_type)),
+        GrowthTimer(Timer::from_seconds(
+            definition.growth_time_s,
+            TimerMode::Once,
+        )),
+        Hydration::default(),
+        Health::new(plant_registry.max_health(plant_type)),
         Transform::from_translation(position.extend(1.)),
     )
 }
 
-fn plant_max_health(plant_type: PlantType) -> i32 {
-    match plant_type {
-        PlantType::Daisy => 2,
-        PlantType::Pineapple(generation) => *PINEAPPLE_HEALTH_CURVE
-            .get(generation as usize)
-            .unwrap_or(&0),
-        PlantType::Dragonfruit => 5,
-        PlantType::Gnome => 10,
-    }
-}
-
-fn plant_radius(plant_type: PlantType) -> f32 {
-    match plant_type {
-        PlantType::Daisy => 30.,
-        PlantType::Pineapple(generation) => *PINEAPPLE_RADIUS_CURVE
-            .get(generation as usize)
-            .unwrap_or(&(0.)),
-        PlantType::Dragonfruit => 45.,
-        PlantType::Gnome => 30.,
-    }
-}
-
 fn fireball(
     spawning_entity: Entity,
     origin: Vec3,
@@ -137,7 +119,7 @@ fn fireball(
         DespawnOnRestart,
         RigidBody::Kinematic,
         Collider::circle(FIREBALL_RADIUS_PX),
-        CollisionLayers::new([GameLayer::Fireball], [GameLayer::Enemy]),
+        CollisionLayers::new([GameLayer::Fireball], [GameLayer::Enemy, GameLayer::Plant]),
         CollisionEventsEnabled,
         Sprite {
             image: plant_assets.fireball.clone(),
@@ -167,6 +149,110 @@ impl Plant {
 #[reflect(Component)]
 pub struct GrowthTimer(Timer);
 
+impl GrowthTimer {
+    /// How far through growing the plant is, `overstep` after the last tick.
+    fn progress(&self, overstep: Duration) -> f32 {
+        let elapsed = self.0.elapsed() + overstep;
+        (elapsed.as_secs_f32() / self.0.duration().as_secs_f32()).min(1.)
+    }
+
+    fn with_remaining(duration_s: f32, remaining_s: f32) -> Self {
+        let mut timer = Timer::from_seconds(duration_s, TimerMode::Once);
+        timer.set_elapsed(Duration::from_secs_f32((duration_s - remaining_s).max(0.)));
+        Self(timer)
+    }
+}
+
+/// A plant as it was when the game was saved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedPlant {
+    plant_type: PlantType,
+    position: (f32, f32),
+    health: i32,
+    /// Time left until the plant is grown, or `None` if it already is.
+    growth_remaining_s: Option<f32>,
+    hydration: f32,
+    burning: bool,
+    /// How long it had been burning for, if it was.
+    burning_s: f32,
+}
+
+impl SavedPlant {
+    pub fn new(
+        plant: &Plant,
+        transform: &Transform,
+        health: &Health,
+        growth_timer: Option<&GrowthTimer>,
+        hydration: &Hydration,
+        burning: Option<&Burning>,
+    ) -> Self {
+        Self {
+            plant_type: plant.plant_type,
+            position: transform.translation.xy().into(),
+            health: health.current(),
+            growth_remaining_s: growth_timer.map(|t| t.0.remaining_secs()),
+            hydration: hydration.level(),
+            burning: burning.is_some(),
+            burning_s: burning.map_or(0., Burning::elapsed_secs),
+        }
+    }
+
+    /// Respawn the plant as it was saved.
+    pub fn spawn(
+        &self,
+        commands: &mut Commands,
+        plant_assets: &PlantAssets,
+        plant_registry: &PlantRegistry,
+    ) -> Option<Entity> {
+        let Some(definition) = plant_registry.get(self.plant_type) else {
+            warn!("No plant definition for {:?}", self.plant_type);
+            return None;
+        };
+        let position = Vec2::from(self.position);
+        let mut entity = commands.spawn(plant(
+            position,
+            plant_assets,
+            plant_registry,
+            definition,
+            self.plant_type,
+        ));
+        entity.insert((
+            Health::new(plant_registry.max_health(self.plant_type)).with_current(self.health),
+            Hydration::with_level(self.hydration),
+        ));
+        if definition.blocks_enemies {
+            entity.insert(EnemyObstacle);
+        }
+        match self.growth_remaining_s {
+            Some(remaining_s) => {
+                entity.insert(GrowthTimer::with_remaining(
+                    definition.growth_time_s,
+                    remaining_s,
+                ));
+            }
+            None => {
+                entity.remove::<GrowthTimer>().insert((
+                    grown_sprite(definition, plant_registry, self.plant_type),
+                    Transform::from_translation(position.extend(1.))
+                        .with_scale(Vec3::splat(GROWN_SCALE)),
+                ));
+            }
+        }
+        if self.burning {
+            entity.insert(Burning::burnt_for(self.burning_s));
+        }
+        Some(entity.id())
+    }
+
+    pub fn position(&self) -> Vec2 {
+        self.position.into()
+    }
+
+    pub fn plant_type(&self) -> PlantType {
+        self.plant_type
+    }
+}
+
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
 #[reflect(Component)]
 pub struct Fireball {
@@ -187,23 +273,11 @@ pub struct Burnable;
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct PlantAssets {
-    #[dependency]
-    daisy: Handle<Image>,
-    #[dependency]
-    dragonfruit: Handle<Image>,
-    #[dependency]
-    pineapple: Handle<Image>,
     #[dependency]
     seedling: Handle<Image>,
     #[dependency]
-    gnome: Handle<Image>,
-    #[dependency]
     fireball: Handle<Image>,
     #[dependency]
-    sow_sounds: Vec<Handle<AudioSource>>,
-    #[dependency]
-    growth_sound: Handle<AudioSource>,
-    #[dependency]
     death_sound: Handle<AudioSource>,
     #[dependency]
     fireball_spawn_sound: Handle<AudioSource>,
@@ -211,7 +285,9 @@ pub struct PlantAssets {
     burn_sound: Handle<AudioSource>,
 }
 
-#[derive(Default, Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[derive(
+    Default, Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect,
+)]
 pub enum PlantType {
     #[default]
     Daisy,
@@ -224,12 +300,18 @@ pub enum PlantType {
 }
 
 impl PlantType {
-    pub fn price(&self) -> f32 {
+    /// The first-generation plant of the same kind, used to look up its [`PlantDefinition`].
+    pub fn species(&self) -> PlantType {
+        match self {
+            PlantType::Pineapple(_) => PlantType::Pineapple(PINEAPPLE_DEFAULT_GENERATION),
+            other => *other,
+        }
+    }
+
+    pub fn generation(&self) -> i32 {
         match self {
-            PlantType::Daisy => DAISY_PRICE,
-            PlantType::Pineapple(_) => PINEAPPLE_PRICE,
-            PlantType::Dragonfruit => DRAGONFRUIT_PRICE,
-            PlantType::Gnome => GNOME_PRICE,
+            PlantType::Pineapple(generation) => *generation,
+            _ => 0,
         }
     }
 }
@@ -262,6 +344,10 @@ impl SeedSelection {
     }
 }
 
+/// A request to change the seed that the player throws.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SelectSeedEvent(pub PlantType);
+
 #[derive(Event, Debug, Default)]
 pub struct SowPlantEvent {
     pub position: Vec2,
@@ -280,46 +366,10 @@ pub struct SpewFireEvent {
     pub origin: Vec3,
 }
 
-#[derive(Event, Debug)]
-pub struct SellDaisyChainEvent {
-    daisy_entities: Vec<Entity>,
-    position: Vec3,
-}
-
-pub fn plant_collision_check(
-    plant_position: Vec2,
-    hit_position: Vec2,
-    plant_type: PlantType,
-) -> bool {
-    let difference = plant_position - hit_position;
-    difference.length() < plant_radius(plant_type)
-}
-
 impl FromWorld for PlantAssets {
     fn from_world(world: &mut World) -> Self {
         let assets = world.resource::<AssetServer>();
         Self {
-            daisy: assets.load_with_settings(
-                "images/plants/daisy.png",
-                |settings: &mut ImageLoaderSettings| {
-                    // Use `nearest` image sampling to preserve pixel art style.
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            dragonfruit: assets.load_with_settings(
-                "images/plants/dragonfruit.png",
-                |settings: &mut ImageLoaderSettings| {
-                    // Use `nearest` image sampling to preserve pixel art style.
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-            pineapple: assets.load_with_settings(
-                "images/plants/pineapple.png",
-                |settings: &mut ImageLoaderSettings| {
-                    // Use `nearest` image sampling to preserve pixel art style.
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
             seedling: assets.load_with_settings(
                 "images/plants/seedling.png",
                 |settings: &mut ImageLoaderSettings| {
@@ -327,13 +377,6 @@ impl FromWorld for PlantAssets {
                     settings.sampler = ImageSampler::nearest();
                 },
             ),
-            gnome: assets.load_with_settings(
-                "images/plants/gnome.png",
-                |settings: &mut ImageLoaderSettings| {
-                    // Use `nearest` image sampling to preserve pixel art style.
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
             fireball: assets.load_with_settings(
                 "images/fireball.png",
                 |settings: &mut ImageLoaderSettings| {
@@ -341,11 +384,6 @@ impl FromWorld for PlantAssets {
                     settings.sampler = ImageSampler::nearest();
                 },
             ),
-            sow_sounds: vec![
-                assets.load("audio/sound_effects/sow1.ogg"),
-                assets.load("audio/sound_effects/sow2.ogg"),
-            ],
-            growth_sound: assets.load("audio/sound_effects/growth.ogg"),
             death_sound: assets.load("audio/sound_effects/death.ogg"),
             fireball_spawn_sound: assets.load("audio/sound_effects/fireball_spawn.ogg"),
             burn_sound: assets.load("audio/sound_effects/burn.ogg"),
@@ -356,40 +394,95 @@ impl FromWorld for PlantAssets {
 fn sow_plants(
     mut commands: Commands,
     plant_assets: Res<PlantAssets>,
+    plant_registry: Res<PlantRegistry>,
+    farm_grid: Res<FarmGrid>,
     mut sow_events: EventReader<SowPlantEvent>,
+    mut rng: ResMut<GameRng>,
 ) {
+    // Tiles sown this frame, which won't be in the grid until the plants are spawned
+    let mut sown_tiles = Vec::new();
     for event in sow_events.read() {
-        println!(
-            "Plant ({:?}) spawned at {:?}",
-            event.seed_type, event.position
-        );
-        commands.spawn(plant(event.position, &plant_assets, event.seed_type));
-
-        let rng = &mut rand::thread_rng();
-        let random_sow_sound = plant_assets.sow_sounds.choose(rng).unwrap().clone();
-        commands.spawn((
-            sound_effect(random_sow_sound),
-            Transform::from_translation(event.position.extend(0.)),
-        ));
+        let Some(definition) = plant_registry.get(event.seed_type) else {
+            warn!("No plant definition for {:?}", event.seed_type);
+            continue;
+        };
+        let Some(tile) = FarmGrid::tile_at(event.position) else {
+            info!("Not sowing at {:?} - outside the farm", event.position);
+            continue;
+        };
+        if farm_grid.is_occupied(tile) || sown_tiles.contains(&tile) {
+            info!(
+                "Not sowing at {:?} - tile {:?} is occupied",
+                event.position, tile
+            );
+            continue;
+        }
+        sown_tiles.push(tile);
+
+        let position = FarmGrid::tile_center(tile);
+        println!("Plant ({:?}) spawned at {:?}", event.seed_type, position);
+        let plant_entity = commands
+            .spawn(plant(
+                position,
+                &plant_assets,
+                &plant_registry,
+                definition,
+                event.seed_type,
+            ))
+            .id();
+        if definition.blocks_enemies {
+            commands.entity(plant_entity).insert(EnemyObstacle);
+        }
+
+        if let Some(random_sow_sound) = definition.sow_sounds.choose(&mut *rng) {
+            commands.spawn((
+                sound_effect(random_sow_sound.clone()),
+                Transform::from_translation(position.extend(0.)),
+            ));
+        }
     }
 }
 
-fn draw_plant_circles(mut painter: ShapePainter, q_plants: Query<(&Transform, &Plant)>) {
+fn select_seed(
+    mut commands: Commands,
+    mut select_seed_events: EventReader<SelectSeedEvent>,
+    q_seed_selection: Query<Entity, With<React<SeedSelection>>>,
+    mut seed_selection: ReactiveMut<SeedSelection>,
+) {
+    for ev in select_seed_events.read() {
+        let Ok(entity) = q_seed_selection.single() else {
+            warn!("No seed selection");
+            return;
+        };
+        if let Ok(selection) = seed_selection.get_mut(&mut commands, entity) {
+            selection.set_seed_type(ev.0);
+        }
+    }
+}
+
+fn draw_plant_circles(
+    mut painter: ShapePainter,
+    q_plants: Query<(&Transform, &Plant)>,
+    plant_registry: Res<PlantRegistry>,
+) {
     painter.color = PLANT_OUTLINE;
     painter.hollow = true;
     painter.thickness = 0.5;
     for (plant_transform, plant) in q_plants {
         painter.transform.translation = plant_transform.translation;
-        painter.circle(plant_radius(plant.plant_type));
+        painter.circle(plant_registry.radius(plant.plant_type));
     }
 }
 
 fn draw_growth(
     mut painter: ShapePainter,
-    q_growing_plants: Query<(&mut Transform, &mut GrowthTimer, &Plant)>,
+    q_growing_plants: Query<(&Transform, &GrowthTimer, &Plant, &Hydration)>,
+    plant_registry: Res<PlantRegistry>,
+    fixed_time: Res<Time<Fixed>>,
+    pause: Res<State<Pause>>,
 ) {
-    for (transform, growth_timer, plant) in q_growing_plants {
-        let plant_radius = plant_radius(plant.plant_type());
+    for (transform, growth_timer, plant, hydration) in q_growing_plants {
+        let plant_radius = plant_registry.radius(plant.plant_type());
         let progress_height_px = plant_radius * 0.2;
         let progress_length_px = plant_radius * 1.;
         let progress_dimens = Vec2::new(progress_length_px, progress_height_px);
@@ -402,9 +495,19 @@ fn draw_growth(
         painter.color = PLANT_GROWTH_BAR_OUTLINE;
         painter.rect(progress_dimens);
 
-        let progress = growth_timer.0.fraction();
+        // Carry on growing between ticks, unless the game is paused
+        let overstep = if pause.get().0 {
+            Duration::ZERO
+        } else {
+            fixed_time.overstep().mul_f32(hydration.growth_rate())
+        };
+        let progress = growth_timer.progress(overstep);
         painter.hollow = false;
-        painter.color = PLANT_GROWTH_FOREGROUND;
+        painter.color = if hydration.is_dry() {
+            PLANT_GROWTH_THIRSTY
+        } else {
+            PLANT_GROWTH_FOREGROUND
+        };
         painter.rect(Vec2::new(
             progress_dimens.x * progress,
             progress_dimens.y * 0.8,
@@ -412,60 +515,46 @@ fn draw_growth(
     }
 }
 
-fn draw_gnome_throw_circles(
-    mut painter: ShapePainter,
-    q_plants: Query<(&Transform, &Plant), Without<GrowthTimer>>,
-) {
-    let gnome_transforms = q_plants
-        .iter()
-        .filter(|(_, p)| p.plant_type() == PlantType::Gnome)
-        .map(|(t, _)| t);
-    for t in gnome_transforms {
-        painter.color = GNOME_THROW_OUTLINE;
-        painter.transform = *t;
-        painter.hollow = true;
-        painter.thickness = 1.0;
-        painter.circle(GNOME_THROW_RADIUS_PX);
-    }
-}
-
 fn tick_growth(
     mut commands: Commands,
-    mut q_growing_plants: Query<(Entity, &Plant, &mut Transform, &mut GrowthTimer)>,
+    mut q_growing_plants: Query<(
+        Entity,
+        &Plant,
+        &mut Transform,
+        &mut GrowthTimer,
+        &mut Hydration,
+        Has<Burning>,
+    )>,
     time: Res<Time>,
-    plant_assets: Res<PlantAssets>,
+    plant_registry: Res<PlantRegistry>,
 ) {
-    for (entity, plant, mut transform, mut growth_timer) in &mut q_growing_plants {
-        growth_timer.0.tick(time.delta());
+    for (entity, plant, mut transform, mut growth_timer, mut hydration, burning) in
+        &mut q_growing_plants
+    {
+        // Plants grow slowly without water
+        let growth_rate = hydration.growth_rate();
+        hydration.drink(time.delta_secs());
+        growth_timer.0.tick(time.delta().mul_f32(growth_rate));
         if growth_timer.0.finished() {
+            let Some(definition) = plant_registry.get(plant.plant_type) else {
+                warn!("No plant definition for {:?}", plant.plant_type);
+                continue;
+            };
+            let mut sprite = grown_sprite(definition, &plant_registry, plant.plant_type);
+            // The new sprite would otherwise lose the tint of a fire that's still burning
+            if burning {
+                sprite.color = BURNING_TINT;
+            }
             commands
                 .entity(entity)
                 .remove::<GrowthTimer>()
                 .remove::<Sprite>()
-                .insert(Sprite {
-                    image: match plant.plant_type {
-                        PlantType::Daisy => plant_assets.daisy.clone(),
-                        PlantType::Pineapple(_) => plant_assets.pineapple.clone(),
-                        PlantType::Dragonfruit => plant_assets.dragonfruit.clone(),
-                        PlantType::Gnome => plant_assets.gnome.clone(),
-                    },
-                    custom_size: match plant.plant_type {
-                        PlantType::Daisy => None,
-                        PlantType::Pineapple(generation) => Some(Vec2::splat(
-                            *PINEAPPLE_SCALE_CURVE
-                                .get(generation as usize)
-                                .unwrap_or(&(0.)),
-                        )),
-                        PlantType::Dragonfruit => Some(Vec2::splat(64.)),
-                        PlantType::Gnome => Some(Vec2::splat(64.)),
-                    },
-                    ..default()
-                });
-
-            transform.scale = Vec3::splat(0.5);
+                .insert(sprite);
+
+            transform.scale = Vec3::splat(GROWN_SCALE);
 
             commands.spawn((
-                sound_effect(plant_assets.growth_sound.clone()),
+                sound_effect(definition.growth_sound.clone()),
                 Transform::from_translation(transform.translation),
             ));
 
@@ -474,13 +563,31 @@ fn tick_growth(
     }
 }
 
+fn grown_sprite(
+    definition: &PlantDefinition,
+    plant_registry: &PlantRegistry,
+    plant_type: PlantType,
+) -> Sprite {
+    let sprite_size = plant_registry
+        .generation(plant_type)
+        .and_then(|g| g.sprite_size);
+    Sprite {
+        image: definition.sprite.clone(),
+        custom_size: sprite_size.map(Vec2::splat),
+        ..default()
+    }
+}
+
 fn burn_stuff(
     mut commands: Commands,
     mut q_fireballs: Query<(Entity, &Transform, &mut Fireball)>,
     mut q_burnables: Query<(Entity, &mut Health, &Transform), With<Burnable>>,
+    q_plants: Query<&Plant>,
     mut collision_event_reader: EventReader<CollisionStarted>,
     mut spawn_smoke_events: EventWriter<SpawnSmokeEvent>,
+    mut ignite_events: EventWriter<IgnitePlantEvent>,
     plant_assets: Res<PlantAssets>,
+    plant_registry: Res<PlantRegistry>,
 ) {
     if collision_event_reader.is_empty() {
         return;
@@ -492,6 +599,38 @@ fn burn_stuff(
         .collect();
     let burnable_entities: Vec<Entity> = q_burnables.iter().map(|(e, _, _)| e).collect();
     for CollisionStarted(entity1, entity2) in collision_event_reader.read() {
+        // Fireballs set other plants on fire
+        let plant_hit = if fireball_entities.contains(entity1) && q_plants.contains(*entity2) {
+            Some((entity1, entity2))
+        } else if q_plants.contains(*entity1) && fireball_entities.contains(entity2) {
+            Some((entity2, entity1))
+        } else {
+            None
+        };
+        if let Some((fireball_entity, plant_entity)) = plant_hit {
+            let Ok((_, _, mut fireball)) = q_fireballs.get_mut(*fireball_entity) else {
+                continue;
+            };
+            if !fireball.active || fireball.spawning_entity == *plant_entity {
+                continue;
+            }
+            // Fireballs fly straight through plants that can't burn
+            let fire_immune = q_plants.get(*plant_entity).is_ok_and(|plant| {
+                plant_registry
+                    .get(plant.plant_type)
+                    .is_some_and(|p| p.fire_immune)
+            });
+            if fire_immune {
+                continue;
+            }
+            fireball.deactivate();
+            commands.entity(*fireball_entity).try_despawn();
+            ignite_events.write(IgnitePlantEvent {
+                plant_entity: *plant_entity,
+            });
+            continue;
+        }
+
         let (fireball_entity, burnable_entity) =
             if fireball_entities.contains(entity1) && burnable_entities.contains(entity2) {
                 (entity1, entity2)
@@ -539,71 +678,6 @@ fn burn_stuff(
     }
 }
 
-fn form_daisy_chains(
-    q_plants: Query<(Entity, &Transform, &Plant), Without<GrowthTimer>>,
-    mut sell_events: EventWriter<SellDaisyChainEvent>,
-) {
-    let daisies: Vec<_> = q_plants
-        .iter()
-        .filter(|(_, _, p)| p.plant_type == PlantType::Daisy)
-        .take(DAISY_CHAIN_LENGTH)
-        .collect();
-
-    // Find 3 daisies
-    if daisies.len() < DAISY_CHAIN_LENGTH {
-        return;
-    }
-    let daisy_entities: Vec<_> = daisies.iter().map(|(e, _, _)| *e).collect();
-    let daisy_positions: Vec<_> = daisies.iter().map(|(_, t, _)| t.translation).collect();
-    sell_events.write(SellDaisyChainEvent {
-        daisy_entities,
-        position: avg_pos(&daisy_positions),
-    });
-}
-
-fn avg_pos(positions: &Vec<Vec3>) -> Vec3 {
-    if positions.is_empty() {
-        warn!("Empty list, no average");
-        return Vec3::ZERO;
-    }
-
-    let mut sum_x = 0.;
-    let mut sum_y = 0.;
-    let mut sum_z = 0.;
-
-    for pos in positions {
-        sum_x += pos.x;
-        sum_y += pos.y;
-        sum_z += pos.z;
-    }
-
-    let count = positions.len() as f32;
-    Vec3::new(sum_x / count, sum_y / count, sum_z / count)
-}
-
-fn sell_daisy_chains(
-    mut commands: Commands,
-    mut sell_events: EventReader<SellDaisyChainEvent>,
-    mut q_bank_account: Query<&mut BankAccount>,
-    mut bank_account_update_events: EventWriter<BankAccountUpdateEvent>,
-    mut get_coin_events: EventWriter<GetCoinEvent>,
-) {
-    for ev in sell_events.read() {
-        info!("Selling daisy chain: {:?}", ev.daisy_entities.iter());
-        for entity in ev.daisy_entities.iter() {
-            commands.entity(*entity).despawn();
-        }
-        let Ok(mut bank_account) = q_bank_account.single_mut() else {
-            warn!("No bank account!");
-            return;
-        };
-        bank_account.credit(DAISY_CHAIN_VALUE);
-        bank_account_update_events.write(BankAccountUpdateEvent);
-
-        get_coin_events.write(GetCoinEvent(ev.position));
-    }
-}
-
 fn damage_plants(
     mut q_plants: Query<(Entity, &mut Health), With<Plant>>,
     mut damage_plant_events: EventReader<DamagePlantEvent>,
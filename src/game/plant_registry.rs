@@ -0,0 +1,226 @@
+//! Data-driven plant stats, loaded from `assets/data/catalog.plants.ron`.
+
+use crate::asset_tracking::LoadResource;
+use crate::game::plant::PlantType;
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::image::{ImageLoaderSettings, ImageSampler};
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+const PLANT_CATALOG_PATH: &str = "data/catalog.plants.ron";
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<PlantRegistry>();
+    app.register_asset_loader(PlantRegistryLoader);
+    app.load_resource_from_path::<PlantRegistry>(PLANT_CATALOG_PATH);
+}
+
+/// Every plant that can be sown, in the order they are offered to the player.
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct PlantRegistry {
+    plants: Vec<PlantDefinition>,
+}
+
+#[derive(Debug, Clone, Reflect)]
+pub struct PlantDefinition {
+    /// The first-generation plant type this definition describes.
+    pub plant_type: PlantType,
+    pub price: f32,
+    pub growth_time_s: f32,
+    /// Damage dealt to an enemy that bites this plant once it's grown.
+    pub strength: i32,
+    /// Whether enemies have to path around this plant rather than through it.
+    pub blocks_enemies: bool,
+    /// Whether this plant can't be set on fire.
+    pub fire_immune: bool,
+    pub sprite: Handle<Image>,
+    pub sow_sounds: Vec<Handle<AudioSource>>,
+    pub growth_sound: Handle<AudioSource>,
+    /// Stats for each generation. Only pineapples have more than one.
+    generations: Vec<PlantGeneration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Reflect)]
+pub struct PlantGeneration {
+    pub health: i32,
+    pub radius: f32,
+    /// Side length of the grown sprite, or `None` to use the image size.
+    #[serde(default)]
+    pub sprite_size: Option<f32>,
+}
+
+impl PlantRegistry {
+    pub fn plants(&self) -> impl Iterator<Item = &PlantDefinition> {
+        self.plants.iter()
+    }
+
+    pub fn get(&self, plant_type: PlantType) -> Option<&PlantDefinition> {
+        let species = plant_type.species();
+        self.plants.iter().find(|p| p.plant_type == species)
+    }
+
+    pub fn generation(&self, plant_type: PlantType) -> Option<&PlantGeneration> {
+        self.get(plant_type)?
+            .generations
+            .get(plant_type.generation() as usize)
+    }
+
+    pub fn max_health(&self, plant_type: PlantType) -> i32 {
+        self.generation(plant_type).map_or(0, |g| g.health)
+    }
+
+    pub fn radius(&self, plant_type: PlantType) -> f32 {
+        self.generation(plant_type).map_or(0., |g| g.radius)
+    }
+
+    pub fn price(&self, plant_type: PlantType) -> f32 {
+        self.get(plant_type).map_or(0., |p| p.price)
+    }
+
+    pub fn strength(&self, plant_type: PlantType) -> i32 {
+        self.get(plant_type).map_or(0, |p| p.strength)
+    }
+}
+
+impl PlantDefinition {
+    pub fn max_generation(&self) -> i32 {
+        self.generations.len() as i32 - 1
+    }
+}
+
+/// The on-disk format of a single plant.
+#[derive(Deserialize)]
+struct PlantDefinitionFile {
+    plant_type: PlantType,
+    price: f32,
+    growth_time_s: f32,
+    #[serde(default)]
+    strength: i32,
+    #[serde(default)]
+    blocks_enemies: bool,
+    #[serde(default)]
+    fire_immune: bool,
+    sprite: String,
+    sow_sounds: Vec<String>,
+    growth_sound: String,
+    generations: Vec<PlantGeneration>,
+}
+
+#[derive(Deserialize)]
+struct PlantCatalogFile {
+    plants: Vec<PlantDefinitionFile>,
+}
+
+#[derive(Default)]
+struct PlantRegistryLoader;
+
+#[derive(Debug, Error)]
+enum PlantRegistryLoaderError {
+    #[error("Could not read plant catalog: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse plant catalog: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("{0} has no generations")]
+    NoGenerations(PlantType),
+}
+
+impl AssetLoader for PlantRegistryLoader {
+    type Asset = PlantRegistry;
+    type Settings = ();
+    type Error = PlantRegistryLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let catalog: PlantCatalogFile = ron::de::from_bytes(&bytes)?;
+
+        let mut plants = Vec::with_capacity(catalog.plants.len());
+        for plant in catalog.plants {
+            if plant.generations.is_empty() {
+                return Err(PlantRegistryLoaderError::NoGenerations(plant.plant_type));
+            }
+            plants.push(PlantDefinition {
+                plant_type: plant.plant_type.species(),
+                price: plant.price,
+                growth_time_s: plant.growth_time_s,
+                strength: plant.strength,
+                blocks_enemies: plant.blocks_enemies,
+                fire_immune: plant.fire_immune,
+                sprite: load_context
+                    .loader()
+                    .with_settings(|settings: &mut ImageLoaderSettings| {
+                        // Use `nearest` image sampling to preserve pixel art style.
+                        settings.sampler = ImageSampler::nearest();
+                    })
+                    .load(plant.sprite),
+                sow_sounds: plant
+                    .sow_sounds
+                    .into_iter()
+                    .map(|path| load_context.load(path))
+                    .collect(),
+                growth_sound: load_context.load(plant.growth_sound),
+                generations: plant.generations,
+            });
+        }
+
+        Ok(PlantRegistry { plants })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["plants.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn catalog() -> PlantCatalogFile {
+        ron::from_str(include_str!("../../assets/data/catalog.plants.ron"))
+            .expect("Could not parse plant catalog")
+    }
+
+    #[test]
+    fn catalog_has_every_plant() {
+        let catalog = catalog();
+        for plant_type in [
+            PlantType::Daisy,
+            PlantType::Pineapple(0),
+            PlantType::Dragonfruit,
+            PlantType::Gnome,
+        ] {
+            let plant = catalog
+                .plants
+                .iter()
+                .find(|p| p.plant_type.species() == plant_type.species())
+                .unwrap_or_else(|| panic!("{} is missing", plant_type));
+            assert!(
+                !plant.generations.is_empty(),
+                "{} has no generations",
+                plant_type
+            );
+            assert_eq!(plant.fire_immune, plant_type == PlantType::Dragonfruit);
+        }
+    }
+
+    #[test]
+    fn catalog_files_exist() {
+        let assets = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        for plant in catalog().plants {
+            for path in [&plant.sprite, &plant.growth_sound]
+                .into_iter()
+                .chain(&plant.sow_sounds)
+            {
+                assert!(assets.join(path).exists(), "{} is missing", path);
+            }
+        }
+    }
+}
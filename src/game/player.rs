@@ -1,10 +1,13 @@
 //! Player-specific behavior.
 
-use crate::PausableSystems;
 use crate::asset_tracking::LoadResource;
+use crate::game::farm::EnemyObstacle;
+use crate::game::inventory::Inventory;
 use crate::game::plant::PlantType;
 use crate::game::player_animation::PlayerAnimation;
+use crate::game::replay::ReplayInput;
 use crate::theme::palette::PLAYER_THROW_OUTLINE;
+use crate::{AppSystems, DrawSystems, PausableSystems};
 use bevy::input::common_conditions::*;
 use bevy::window::PrimaryWindow;
 use bevy::{
@@ -26,15 +29,19 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_systems(
         Update,
-        on_click
-            .run_if(input_just_pressed(MouseButton::Left))
+        (
+            on_click.run_if(input_just_pressed(MouseButton::Left)),
+            check_touch,
+        )
+            .in_set(AppSystems::RecordInput)
             .in_set(PausableSystems),
     );
-    app.add_systems(Update, check_touch.in_set(PausableSystems));
 
     app.add_systems(
         Update,
-        draw_player_circle.run_if(resource_exists::<PlayerAssets>),
+        draw_player_circle
+            .run_if(resource_exists::<PlayerAssets>)
+            .in_set(DrawSystems),
     );
 }
 
@@ -51,6 +58,7 @@ pub fn player(
     (
         Name::new("Player"),
         Player,
+        EnemyObstacle,
         Sprite {
             image: player_assets.farmer.clone(),
             texture_atlas: Some(TextureAtlas {
@@ -61,6 +69,7 @@ pub fn player(
         },
         Transform::from_translation(Vec3::new(-0.0, 0.0, 1.0)),
         player_animation,
+        Inventory::default(),
         children![(
             Name::new("Chain"),
             Sprite {
@@ -86,7 +95,7 @@ pub struct PlayerAssets {
     #[dependency]
     farmer: Handle<Image>,
     #[dependency]
-    chain: Handle<Image>,
+    pub chain: Handle<Image>,
     #[dependency]
     pub throw_sounds: Vec<Handle<AudioSource>>,
 }
@@ -167,14 +176,17 @@ impl FromWorld for PlayerAssets {
 
 fn on_click(
     q_windows: Query<&Window, With<PrimaryWindow>>,
-    q_transform: Query<&Transform, With<Camera>>,
-    mut events: EventWriter<PlayerClickEvent>,
+    q_camera: Query<(&Camera, &GlobalTransform)>,
+    mut events: EventWriter<ReplayInput>,
 ) {
     if let Ok(window) = q_windows.single() {
-        if let Ok(transform) = q_transform.single() {
+        if let Ok((camera, camera_transform)) = q_camera.single() {
             if let Some(window_position) = window.cursor_position() {
-                let world_position = window_to_world(window_position, window, transform);
-                events.write(PlayerClickEvent(world_position));
+                if let Some(world_position) =
+                    window_to_world(window_position, camera, camera_transform)
+                {
+                    events.write(ReplayInput::Click(world_position.into()));
+                }
             }
         }
     }
@@ -182,29 +194,30 @@ fn on_click(
 
 fn check_touch(
     touches: Res<Touches>,
-    q_windows: Query<&Window, With<PrimaryWindow>>,
-    q_transform: Query<&Transform, With<Camera>>,
-    mut events: EventWriter<PlayerClickEvent>,
+    q_camera: Query<(&Camera, &GlobalTransform)>,
+    mut events: EventWriter<ReplayInput>,
 ) {
+    // More than one finger is a pinch or a pan, not a throw
+    if touches.iter().count() > 1 {
+        return;
+    }
     if let Some(window_position) = touches.first_pressed_position() {
-        if let Ok(window) = q_windows.single() {
-            if let Ok(transform) = q_transform.single() {
-                let world_position = window_to_world(window_position, window, transform);
-                events.write(PlayerClickEvent(world_position));
+        if let Ok((camera, camera_transform)) = q_camera.single() {
+            if let Some(world_position) = window_to_world(window_position, camera, camera_transform)
+            {
+                events.write(ReplayInput::Click(world_position.into()));
             }
         }
     }
 }
 
-fn window_to_world(position: Vec2, window: &Window, camera: &Transform) -> Vec2 {
-    let norm = Vec3::new(
-        position.x - window.width() / 2.,
-        -1. * (position.y - window.height() / 2.),
-        0.,
-    );
-
-    let world_pos_3d = *camera * norm;
-    Vec2::new(world_pos_3d.x, world_pos_3d.y)
+/// The point on the farm under a window position, seen through the camera (with its zoom and pan).
+pub fn window_to_world(
+    position: Vec2,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+) -> Option<Vec2> {
+    camera.viewport_to_world_2d(camera_transform, position).ok()
 }
 
 fn draw_player_circle(mut painter: ShapePainter, q_player: Query<&Transform, With<Player>>) {
@@ -1,10 +1,12 @@
 //! Player sprite animation.
 
 use bevy::prelude::*;
-use rand::prelude::*;
+use rand::prelude::SliceRandom;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 use crate::game::player::{Player, ThrowSeedEvent};
+use crate::game::rng::GameRng;
 use crate::{AppSystems, PausableSystems, audio::sound_effect, game::player::PlayerAssets};
 
 pub(super) fn plugin(app: &mut App) {
@@ -12,13 +14,23 @@ pub(super) fn plugin(app: &mut App) {
     app.register_type::<PlayerAnimation>();
     app.add_systems(
         Update,
-        (
-            update_animation_timer.in_set(AppSystems::TickTimers),
-            (animate_throw_seed, update_animation_atlas)
-                .chain()
-                .run_if(resource_exists::<PlayerAssets>)
-                .in_set(AppSystems::Update),
-        )
+        update_animation_atlas
+            .run_if(resource_exists::<PlayerAssets>)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+    // The farmer can't throw while an animation is playing, so it times out in step with the game
+    app.add_systems(
+        FixedUpdate,
+        update_animation_timer
+            .in_set(AppSystems::TickTimers)
+            .in_set(PausableSystems),
+    );
+    // Throws pick a random sound, so they happen in step with the rest of the game
+    app.add_systems(
+        FixedUpdate,
+        animate_throw_seed
+            .run_if(resource_exists::<PlayerAssets>)
             .in_set(PausableSystems),
     );
 }
@@ -28,13 +40,17 @@ fn animate_throw_seed(
     mut events: EventReader<ThrowSeedEvent>,
     mut player_animation: Single<&mut PlayerAnimation, With<Player>>,
     player_assets: Res<PlayerAssets>,
+    mut rng: ResMut<GameRng>,
 ) {
     for ev in events.read() {
         if !ev.from_player {
             continue;
         }
-        let rng = &mut thread_rng();
-        let throw_sound = player_assets.throw_sounds.choose(rng).unwrap().clone();
+        let throw_sound = player_assets
+            .throw_sounds
+            .choose(&mut *rng)
+            .unwrap()
+            .clone();
         let Some(throw_origin) = ev.path.first() else {
             warn!("No origin for throw path");
             continue;
@@ -77,13 +93,20 @@ pub struct PlayerAnimation {
     state: PlayerAnimationState,
 }
 
-#[derive(Reflect, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect)]
 pub enum PlayerAnimationState {
     Idling,
     Planting(bool),
     Mailing,
 }
 
+/// What the farmer was doing when the game was saved.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SavedPlayerAnimation {
+    state: PlayerAnimationState,
+    elapsed_s: f32,
+}
+
 impl PlayerAnimation {
     const IDLE_FRAME: usize = 0;
     const IDLE_INTERVAL: Duration = Duration::from_millis(500);
@@ -97,6 +120,28 @@ impl PlayerAnimation {
         self.frame
     }
 
+    pub fn state(&self) -> &PlayerAnimationState {
+        &self.state
+    }
+
+    pub fn save(&self) -> SavedPlayerAnimation {
+        SavedPlayerAnimation {
+            state: self.state,
+            elapsed_s: self.timer.elapsed_secs(),
+        }
+    }
+
+    /// Pick the animation up part way through, so a busy farmer stays busy for as long as they
+    /// were going to.
+    pub fn load(saved: &SavedPlayerAnimation) -> Self {
+        let mut animation = Self::new();
+        animation.update_state(saved.state);
+        animation
+            .timer
+            .set_elapsed(Duration::from_secs_f32(saved.elapsed_s));
+        animation
+    }
+
     fn idling() -> Self {
         Self {
             timer: Timer::new(Self::IDLE_INTERVAL, TimerMode::Once),
@@ -130,6 +175,11 @@ impl PlayerAnimation {
         Self::idling()
     }
 
+    /// Whether the farmer is in the middle of doing something, and can't throw.
+    pub fn is_busy(&self) -> bool {
+        self.state != PlayerAnimationState::Idling
+    }
+
     pub fn update_timer(&mut self, delta: Duration) {
         self.timer.tick(delta);
         if self.timer.finished() {
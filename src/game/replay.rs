@@ -0,0 +1,377 @@
+//! Recording every input of a game to a replay file, and playing replays back.
+//!
+//! Input handlers send a [`ReplayInput`] rather than the game event it stands for, so every input
+//! is recorded in one stream, in the order it was made, and stamped with the fixed tick that
+//! handles it. Together with the [`GameRng`] seed, that's enough to play the same game again,
+//! restarts and all. Each session is recorded to `replay.ron` when it's paused or left, and
+//! `--replay <path>` plays a recording back instead of taking input.
+//!
+//! A game continued from a save isn't recorded, since a replay can only start from a new game.
+
+use crate::game::farm::{RestartGameEvent, restart_game};
+use crate::game::mail_order::OrderSeedsEvent;
+use crate::game::plant::{PlantType, SelectSeedEvent};
+use crate::game::player::PlayerClickEvent;
+use crate::game::rng::{GameRng, start_rng};
+use crate::game::save::PendingSave;
+use crate::game::water::WaterClickEvent;
+use crate::screens::Screen;
+use crate::{AppSystems, PausableSystems, Pause};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thiserror::Error;
+
+const REPLAY_PATH: &str = "replay.ron";
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<GameTick>();
+    app.init_resource::<GameTick>();
+    app.init_resource::<ReplayRecorder>();
+    app.add_event::<ReplayInput>();
+
+    if let Some(path) = replay_path_from_args() {
+        match Replay::read(&path) {
+            Ok(replay) => {
+                info!("Playing back {} inputs from {}", replay.inputs.len(), path);
+                app.insert_resource(ReplayPlayback::new(replay));
+            }
+            Err(e) => warn!("{}", e),
+        }
+    }
+
+    // Live input is ignored while a replay is playing
+    app.configure_sets(
+        Update,
+        AppSystems::RecordInput.run_if(not(resource_exists::<ReplayPlayback>)),
+    );
+
+    app.add_systems(OnEnter(Screen::Gameplay), restart_replay.after(start_rng));
+    app.add_systems(
+        FixedUpdate,
+        (
+            count_ticks.in_set(AppSystems::TickTimers),
+            (
+                record_inputs.run_if(not(resource_exists::<ReplayPlayback>)),
+                play_back_inputs.run_if(resource_exists::<ReplayPlayback>),
+            )
+                .in_set(AppSystems::RecordInput),
+        )
+            .run_if(in_state(Pause(false))),
+    );
+    // A restarted game counts its ticks from zero, from the tick that restarts it
+    app.add_systems(
+        FixedUpdate,
+        restart_ticks
+            .run_if(on_event::<RestartGameEvent>)
+            .before(restart_game)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        OnEnter(Pause(true)),
+        save_replay.run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), save_replay);
+}
+
+/// The number of fixed ticks since the game began, not counting time spent paused.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Reflect)]
+#[reflect(Resource)]
+pub struct GameTick(pub u64);
+
+impl GameTick {
+    /// The game time this many ticks add up to.
+    pub fn elapsed(&self) -> Duration {
+        Time::<Fixed>::default().timestep().mul_f64(self.0 as f64)
+    }
+}
+
+/// A recorded game: the seed it was played with and every input, in order.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Replay {
+    seed: u64,
+    inputs: Vec<RecordedInput>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct RecordedInput {
+    tick: u64,
+    input: ReplayInput,
+}
+
+/// Something the player did, sent by input handlers in place of the game event it stands for.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ReplayInput {
+    Click((f32, f32)),
+    Water((f32, f32)),
+    SelectSeed(PlantType),
+    OrderSeeds {
+        plant_type: PlantType,
+        quantity: u32,
+        express: bool,
+    },
+    Restart,
+}
+
+/// The game events that inputs stand for.
+#[derive(SystemParam)]
+struct InputEvents<'w> {
+    click_events: EventWriter<'w, PlayerClickEvent>,
+    water_click_events: EventWriter<'w, WaterClickEvent>,
+    select_seed_events: EventWriter<'w, SelectSeedEvent>,
+    order_seeds_events: EventWriter<'w, OrderSeedsEvent>,
+    restart_events: EventWriter<'w, RestartGameEvent>,
+}
+
+impl InputEvents<'_> {
+    fn send(&mut self, input: ReplayInput) {
+        match input {
+            ReplayInput::Click((x, y)) => {
+                self.click_events.write(PlayerClickEvent(Vec2::new(x, y)));
+            }
+            ReplayInput::Water((x, y)) => {
+                self.water_click_events
+                    .write(WaterClickEvent(Vec2::new(x, y)));
+            }
+            ReplayInput::SelectSeed(plant_type) => {
+                self.select_seed_events.write(SelectSeedEvent(plant_type));
+            }
+            ReplayInput::OrderSeeds {
+                plant_type,
+                quantity,
+                express,
+            } => {
+                self.order_seeds_events.write(OrderSeedsEvent {
+                    plant_type,
+                    quantity,
+                    express,
+                });
+            }
+            ReplayInput::Restart => {
+                self.restart_events.write_default();
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("Could not access replay file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not write replay: {0}")]
+    Serialize(#[from] ron::Error),
+    #[error("Could not parse replay: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[cfg(target_family = "wasm")]
+    #[error("Replays aren't supported on this platform")]
+    Unsupported,
+}
+
+impl Replay {
+    #[cfg(not(target_family = "wasm"))]
+    fn write(&self) -> Result<(), ReplayError> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        std::fs::write(REPLAY_PATH, text)?;
+        Ok(())
+    }
+
+    #[cfg(target_family = "wasm")]
+    fn write(&self) -> Result<(), ReplayError> {
+        Err(ReplayError::Unsupported)
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn read(path: &str) -> Result<Self, ReplayError> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(ron::from_str(&text)?)
+    }
+
+    #[cfg(target_family = "wasm")]
+    fn read(_path: &str) -> Result<Self, ReplayError> {
+        Err(ReplayError::Unsupported)
+    }
+}
+
+/// The game being recorded, if it's being recorded.
+#[derive(Resource, Debug, Default)]
+pub struct ReplayRecorder(pub Option<Replay>);
+
+/// A replay being fed back into the game in place of live input.
+#[derive(Resource, Debug)]
+pub struct ReplayPlayback {
+    replay: Replay,
+    next: usize,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        Self { replay, next: 0 }
+    }
+}
+
+/// The replay file named by `--replay <path>`, if any.
+fn replay_path_from_args() -> Option<String> {
+    std::env::args().skip_while(|arg| arg != "--replay").nth(1)
+}
+
+fn count_ticks(mut tick: ResMut<GameTick>) {
+    tick.0 += 1;
+}
+
+fn restart_ticks(mut tick: ResMut<GameTick>) {
+    *tick = GameTick::default();
+}
+
+/// Start recording (or playing back) from the first tick of a new game.
+pub fn restart_replay(
+    mut tick: ResMut<GameTick>,
+    mut recorder: ResMut<ReplayRecorder>,
+    playback: Option<ResMut<ReplayPlayback>>,
+    pending_save: Option<Res<PendingSave>>,
+    mut rng: ResMut<GameRng>,
+) {
+    *tick = GameTick::default();
+    if let Some(mut playback) = playback {
+        playback.next = 0;
+        rng.set_seed(playback.replay.seed);
+    }
+    if pending_save.is_some() {
+        info!("Not recording a game continued from a save");
+        recorder.0 = None;
+        return;
+    }
+    recorder.0 = Some(Replay {
+        seed: rng.seed(),
+        inputs: Vec::new(),
+    });
+}
+
+/// Record live inputs, in the order they were made, and pass them on to the game.
+fn record_inputs(
+    mut recorder: ResMut<ReplayRecorder>,
+    mut inputs: EventReader<ReplayInput>,
+    mut input_events: InputEvents,
+    tick: Res<GameTick>,
+) {
+    for &input in inputs.read() {
+        if let Some(replay) = &mut recorder.0 {
+            replay.inputs.push(RecordedInput {
+                tick: tick.0,
+                input,
+            });
+        }
+        input_events.send(input);
+    }
+}
+
+fn play_back_inputs(
+    mut playback: ResMut<ReplayPlayback>,
+    mut live_inputs: EventReader<ReplayInput>,
+    mut input_events: InputEvents,
+    tick: Res<GameTick>,
+) {
+    // Buttons still send inputs during playback, but only the recording is played
+    live_inputs.clear();
+    let playback = &mut *playback;
+    while let Some(recorded) = playback.replay.inputs.get(playback.next) {
+        if recorded.tick > tick.0 {
+            break;
+        }
+        input_events.send(recorded.input);
+        playback.next += 1;
+    }
+}
+
+fn save_replay(recorder: Res<ReplayRecorder>, playback: Option<Res<ReplayPlayback>>) {
+    // Don't overwrite the recording being played
+    if playback.is_some() {
+        return;
+    }
+    let Some(replay) = &recorder.0 else {
+        return;
+    };
+    match replay.write() {
+        Ok(()) => info!("Recorded {} inputs", replay.inputs.len()),
+        Err(e) => warn!("{}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::sim::{Sim, SimPlant};
+
+    /// The centre of the tile containing (100, 0).
+    const TILE: Vec2 = Vec2::new(128., 64.);
+
+    #[test]
+    fn replay_repeats_a_recorded_game() {
+        let mut sim = Sim::new();
+        sim.order(PlantType::Pineapple(0), 1, true);
+        sim.advance(2.5);
+        sim.select_seed(PlantType::Pineapple(0));
+        sim.click(Vec2::new(100., 0.));
+        sim.advance(1.);
+        sim.select_seed(PlantType::Daisy);
+        sim.click(Vec2::new(-100., -150.));
+        sim.advance(1.);
+
+        let mut replayed = Sim::new();
+        replayed.play(sim.recording());
+        replayed.advance(4.5);
+
+        let by_position = |a: &SimPlant, b: &SimPlant| a.position.x.total_cmp(&b.position.x);
+        let mut plants = sim.plants();
+        plants.sort_by(by_position);
+        let mut replayed_plants = replayed.plants();
+        replayed_plants.sort_by(by_position);
+        assert_eq!(plants.len(), 2);
+        assert_eq!(replayed_plants, plants);
+    }
+
+    #[test]
+    fn replay_repeats_a_restart() {
+        let mut sim = Sim::new();
+        sim.click(Vec2::new(100., 0.));
+        sim.advance(1.5);
+        sim.restart();
+        sim.advance(0.5);
+        sim.click(Vec2::new(-100., -150.));
+        sim.advance(1.5);
+        assert_eq!(sim.plant_at(TILE), None);
+
+        let mut replayed = Sim::new();
+        replayed.play(sim.recording());
+        replayed.advance(4.);
+        assert_eq!(replayed.plant_at(TILE), None);
+        assert_eq!(
+            replayed.plant_at(Vec2::new(-128., -192.)),
+            sim.plant_at(Vec2::new(-128., -192.))
+        );
+        assert!(sim.plant_at(Vec2::new(-128., -192.)).is_some());
+    }
+
+    #[test]
+    fn changing_the_seed_mid_game_leaves_the_replay_alone() {
+        let mut sim = Sim::new();
+        let seed = sim.seed();
+        sim.click(Vec2::new(100., 0.));
+        sim.advance(1.);
+        sim.set_next_seed(seed + 1);
+        sim.restart();
+        sim.advance(0.5);
+        sim.click(Vec2::new(-100., -150.));
+        sim.advance(1.5);
+        assert_eq!(sim.seed(), seed);
+
+        let mut replayed = Sim::new();
+        replayed.play(sim.recording());
+        replayed.advance(3.);
+        assert_eq!(replayed.seed(), seed);
+        assert_eq!(
+            replayed.plant_at(Vec2::new(-128., -192.)),
+            sim.plant_at(Vec2::new(-128., -192.))
+        );
+    }
+}
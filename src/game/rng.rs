@@ -0,0 +1,185 @@
+//! The single source of randomness for gameplay.
+//!
+//! Every gameplay system draws from [`GameRng`], so a run can be reproduced from its seed.
+//! Pass `--seed <number>` on the command line or pick one in the settings menu. A seed picked in
+//! the menu waits for the next new game, so it never changes a game that's being recorded.
+
+use crate::PausableSystems;
+use crate::game::farm::{RestartGameEvent, restart_game};
+use crate::screens::Screen;
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+pub(super) fn plugin(app: &mut App) {
+    let seed = seed_from_args(std::env::args()).unwrap_or_else(random_seed);
+    app.insert_resource(GameRng::new(seed));
+    app.insert_resource(NextSeed(seed));
+
+    app.add_systems(OnEnter(Screen::Gameplay), start_rng);
+    // Restart in the fixed tick that restarts the rest of the game
+    app.add_systems(
+        FixedUpdate,
+        restart_rng
+            .run_if(on_event::<RestartGameEvent>)
+            .before(restart_game)
+            .in_set(PausableSystems),
+    );
+}
+
+/// A seeded random number generator, restarted from its seed whenever a game begins.
+///
+/// It counts the 32-bit words it has drawn, so a save can bring it back to the same point.
+#[derive(Resource, Debug, Clone)]
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+    draws: u64,
+}
+
+/// The seed the next new game starts with. Restarting a game keeps the seed it was started with.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NextSeed(pub u64);
+
+/// Where the random numbers had got to when the game was saved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SavedRng {
+    seed: u64,
+    draws: u64,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            draws: 0,
+        }
+    }
+
+    pub fn save(&self) -> SavedRng {
+        SavedRng {
+            seed: self.seed,
+            draws: self.draws,
+        }
+    }
+
+    /// Pick up the random numbers where the save left them.
+    pub fn load(saved: &SavedRng) -> Self {
+        let mut rng = Self::new(saved.seed);
+        for _ in 0..saved.draws {
+            rng.next_u32();
+        }
+        rng
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Switch to a different seed, from the start of its random numbers. Only call this as a game
+    /// begins, before anything has drawn from it.
+    pub fn set_seed(&mut self, seed: u64) {
+        *self = Self::new(seed);
+    }
+
+    /// Replay the random numbers from the start of the seed.
+    fn restart(&mut self) {
+        *self = Self::new(self.seed);
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.draws += 1;
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.draws += 2;
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.draws += dest.len().div_ceil(4) as u64;
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.draws += dest.len().div_ceil(4) as u64;
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+/// A fresh seed, kept short enough to read out in a bug report.
+pub fn random_seed() -> u64 {
+    rand::random::<u32>().into()
+}
+
+/// The seed given as `--seed <number>`, if any.
+fn seed_from_args(args: impl Iterator<Item = String>) -> Option<u64> {
+    let mut args = args.skip_while(|arg| arg != "--seed").skip(1);
+    let arg = args.next()?;
+    match arg.parse() {
+        Ok(seed) => Some(seed),
+        Err(e) => {
+            warn!("Ignoring seed {:?}: {}", arg, e);
+            None
+        }
+    }
+}
+
+/// Begin a new game with the seed picked for it.
+pub fn start_rng(mut rng: ResMut<GameRng>, next_seed: Res<NextSeed>) {
+    info!("Starting game with seed {}", next_seed.0);
+    rng.set_seed(next_seed.0);
+}
+
+fn restart_rng(mut rng: ResMut<GameRng>) {
+    info!("Restarting game with seed {}", rng.seed());
+    rng.restart();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter().map(|arg| arg.to_string())
+    }
+
+    fn draw(rng: &mut GameRng) -> Vec<u64> {
+        (0..8).map(|_| rng.next_u64()).collect()
+    }
+
+    #[test]
+    fn seed_is_read_from_the_command_line() {
+        assert_eq!(seed_from_args(args(&["game", "--seed", "42"])), Some(42));
+        assert_eq!(seed_from_args(args(&["game", "--seed", "forty"])), None);
+        assert_eq!(seed_from_args(args(&["game", "--seed"])), None);
+        assert_eq!(seed_from_args(args(&["game"])), None);
+    }
+
+    #[test]
+    fn same_seed_draws_the_same_numbers() {
+        let mut rng = GameRng::new(42);
+        let numbers = draw(&mut rng);
+        assert_eq!(draw(&mut GameRng::new(42)), numbers);
+        assert_ne!(draw(&mut GameRng::new(43)), numbers);
+
+        rng.restart();
+        assert_eq!(draw(&mut rng), numbers);
+    }
+
+    #[test]
+    fn loaded_rng_carries_on_where_it_was_saved() {
+        let mut rng = GameRng::new(42);
+        rng.next_u32();
+        rng.next_u64();
+        rng.fill_bytes(&mut [0; 5]);
+
+        let mut loaded = GameRng::load(&rng.save());
+        assert_eq!(draw(&mut loaded), draw(&mut rng));
+    }
+}
@@ -0,0 +1,386 @@
+//! Saving an in-progress farm to disk, and picking it up again from the main menu.
+//!
+//! The game is saved whenever it's paused, and the save is deleted once the game is over. Saves
+//! are only written on native builds.
+
+use crate::Pause;
+use crate::game::daisy_chain::{
+    DaisyChainParcel, DaisyChains, SavedDaisyChain, SavedDaisyChainParcel,
+};
+use crate::game::enemy::{BiteCooldown, Enemy, EnemyKind, EnemyPath, SavedEnemy};
+use crate::game::enemy_registry::EnemyRegistry;
+use crate::game::farm::{BankAccount, BankAccountUpdateEvent, ThrowQueue};
+use crate::game::fire::Burning;
+use crate::game::health::Health;
+use crate::game::inventory::{CarriedSeeds, Inventory};
+use crate::game::ledger::Transaction;
+use crate::game::mail_order::{MailOrder, SavedMailOrder};
+use crate::game::plant::{GrowthTimer, Plant, PlantAssets, PlantType, SavedPlant};
+use crate::game::plant_registry::PlantRegistry;
+use crate::game::player::{Player, PlayerAssets};
+use crate::game::player_animation::{PlayerAnimation, SavedPlayerAnimation};
+use crate::game::replay::GameTick;
+use crate::game::rng::{GameRng, SavedRng};
+use crate::game::seed::{SavedSeed, Seed, SeedAssets, SeedFlight, SeedPath};
+use crate::game::water::{Hydration, SavedWater, Water, WaterTank};
+use crate::game::wave::{SavedWaveState, WaveState};
+use crate::screens::Screen;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+const SAVE_PATH: &str = "savegame.ron";
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(Pause(true)),
+        capture_save
+            .pipe(write_save)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(
+        Update,
+        load_pending_save.run_if(
+            in_state(Screen::Gameplay)
+                .and(resource_exists::<PendingSave>)
+                .and(resource_exists::<PlantAssets>)
+                .and(resource_exists::<PlantRegistry>)
+                .and(resource_exists::<EnemyRegistry>)
+                .and(resource_exists::<SeedAssets>)
+                .and(resource_exists::<PlayerAssets>),
+        ),
+    );
+}
+
+/// Everything needed to pick a game up where it was left.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveGame {
+    balance: f32,
+    water: f32,
+    wave: SavedWaveState,
+    plants: Vec<SavedPlant>,
+    enemies: Vec<SavedEnemy>,
+    seeds: Vec<SavedSeed>,
+    inventory: Vec<(PlantType, u32)>,
+    orders: Vec<SavedMailOrder>,
+    rng: SavedRng,
+    daisy_chains: Vec<SavedDaisyChain>,
+    parcels: Vec<SavedDaisyChainParcel>,
+    water_in_flight: Vec<SavedWater>,
+    throw_queue: Vec<((i32, i32), PlantType)>,
+    player_animation: SavedPlayerAnimation,
+    transactions: Vec<Transaction>,
+    tick: GameTick,
+}
+
+/// A save to restore once the gameplay screen is up.
+#[derive(Resource, Debug)]
+pub struct PendingSave(pub SaveGame);
+
+#[derive(Debug, Error)]
+pub enum SaveError {
+    #[error("Could not access save file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not write save: {0}")]
+    Serialize(#[from] ron::Error),
+    #[error("Could not parse save: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[cfg(target_family = "wasm")]
+    #[error("Saving isn't supported on this platform")]
+    Unsupported,
+}
+
+impl SaveGame {
+    #[cfg(not(target_family = "wasm"))]
+    fn write(&self) -> Result<(), SaveError> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        std::fs::write(SAVE_PATH, text)?;
+        Ok(())
+    }
+
+    #[cfg(target_family = "wasm")]
+    fn write(&self) -> Result<(), SaveError> {
+        Err(SaveError::Unsupported)
+    }
+
+    /// Read the saved game, if there is one.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn read() -> Result<Self, SaveError> {
+        let text = std::fs::read_to_string(SAVE_PATH)?;
+        Ok(ron::from_str(&text)?)
+    }
+
+    #[cfg(target_family = "wasm")]
+    pub fn read() -> Result<Self, SaveError> {
+        Err(SaveError::Unsupported)
+    }
+
+    pub fn exists() -> bool {
+        cfg!(not(target_family = "wasm")) && std::path::Path::new(SAVE_PATH).exists()
+    }
+
+    /// Delete the saved game, if there is one.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn delete() -> Result<(), SaveError> {
+        match std::fs::remove_file(SAVE_PATH) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(target_family = "wasm")]
+    pub fn delete() -> Result<(), SaveError> {
+        Ok(())
+    }
+}
+
+/// Everything about the game in progress, or `None` if there isn't one.
+pub(super) fn capture_save(
+    q_bank_account: Query<&BankAccount>,
+    q_tanks: Query<&WaterTank>,
+    q_plants: Query<(
+        &Plant,
+        &Transform,
+        &Health,
+        Option<&GrowthTimer>,
+        &Hydration,
+        Option<&Burning>,
+    )>,
+    q_enemies: Query<
+        (
+            &EnemyKind,
+            &Transform,
+            &Health,
+            Option<&BiteCooldown>,
+            Option<&EnemyPath>,
+            Option<&CarriedSeeds>,
+        ),
+        With<Enemy>,
+    >,
+    q_seeds: Query<(&PlantType, &Transform, &SeedPath, &SeedFlight), With<Seed>>,
+    q_parcels: Query<(&Transform, &DaisyChainParcel, &SeedFlight)>,
+    q_water: Query<(&Transform, &Water)>,
+    q_orders: Query<&MailOrder>,
+    q_inventory: Query<&Inventory, With<Player>>,
+    q_player_animation: Query<&PlayerAnimation, With<Player>>,
+    wave_state: Res<WaveState>,
+    rng: Res<GameRng>,
+    tick: Res<GameTick>,
+    daisy_chains: Res<DaisyChains>,
+    throw_queue: Res<ThrowQueue>,
+) -> Option<SaveGame> {
+    let bank_account = q_bank_account.single().ok()?;
+    Some(SaveGame {
+        balance: bank_account.balance(),
+        water: q_tanks.single().map_or(0., |t| t.level()),
+        wave: wave_state.save(),
+        plants: q_plants
+            .iter()
+            .map(
+                |(plant, transform, health, growth_timer, hydration, burning)| {
+                    SavedPlant::new(plant, transform, health, growth_timer, hydration, burning)
+                },
+            )
+            .collect(),
+        enemies: q_enemies
+            .iter()
+            .map(|(kind, transform, health, bite_cooldown, path, carried)| {
+                // The plant it's after is saved by where it is
+                let path = path.and_then(|path| {
+                    let (_, target, ..) = q_plants.get(path.target()).ok()?;
+                    Some((target.translation.xy(), path))
+                });
+                SavedEnemy::new(*kind, transform, health, bite_cooldown, path, carried)
+            })
+            .collect(),
+        seeds: q_seeds
+            .iter()
+            .map(|(plant_type, transform, path, flight)| {
+                SavedSeed::new(*plant_type, transform, path, flight)
+            })
+            .collect(),
+        inventory: q_inventory.single().map_or(Vec::new(), Inventory::save),
+        orders: q_orders.iter().map(SavedMailOrder::new).collect(),
+        rng: rng.save(),
+        daisy_chains: daisy_chains.save(),
+        parcels: q_parcels
+            .iter()
+            .map(|(transform, parcel, flight)| {
+                SavedDaisyChainParcel::new(transform, parcel, flight)
+            })
+            .collect(),
+        water_in_flight: q_water
+            .iter()
+            .map(|(transform, water)| SavedWater::new(transform, water))
+            .collect(),
+        throw_queue: throw_queue.save(),
+        player_animation: q_player_animation.single().ok()?.save(),
+        transactions: bank_account
+            .transactions()
+            .iter()
+            .map(Transaction::saved)
+            .collect(),
+        tick: *tick,
+    })
+}
+
+fn write_save(In(save): In<Option<SaveGame>>) {
+    let Some(save) = save else {
+        return;
+    };
+    match save.write() {
+        Ok(()) => info!(
+            "Saved {} plants, {} enemies and {} seeds",
+            save.plants.len(),
+            save.enemies.len(),
+            save.seeds.len()
+        ),
+        Err(e) => warn!("{}", e),
+    }
+}
+
+pub(super) fn load_pending_save(
+    mut commands: Commands,
+    pending_save: Res<PendingSave>,
+    mut q_bank_account: Query<&mut BankAccount>,
+    mut q_tanks: Query<&mut WaterTank>,
+    mut bank_account_update_events: EventWriter<BankAccountUpdateEvent>,
+    mut wave_state: ResMut<WaveState>,
+    mut q_inventory: Query<&mut Inventory, With<Player>>,
+    mut q_player_animation: Query<&mut PlayerAnimation, With<Player>>,
+    (mut rng, mut tick): (ResMut<GameRng>, ResMut<GameTick>),
+    mut daisy_chains: ResMut<DaisyChains>,
+    mut throw_queue: ResMut<ThrowQueue>,
+    plant_assets: Res<PlantAssets>,
+    plant_registry: Res<PlantRegistry>,
+    enemy_registry: Res<EnemyRegistry>,
+    seed_assets: Res<SeedAssets>,
+    player_assets: Res<PlayerAssets>,
+) {
+    // Wait for the level to be spawned
+    let Ok(mut bank_account) = q_bank_account.single_mut() else {
+        return;
+    };
+    let save = &pending_save.0;
+    info!("Loading saved game");
+
+    bank_account.load(save.balance, save.transactions.clone());
+    bank_account_update_events.write_default();
+    for mut tank in &mut q_tanks {
+        tank.set_level(save.water);
+    }
+    *wave_state = WaveState::load(&save.wave);
+    // Entities don't survive a save, so anything pointing at a plant finds it again by position
+    let plants: Vec<(Vec2, PlantType, Entity)> = save
+        .plants
+        .iter()
+        .filter_map(|plant| {
+            let entity = plant.spawn(&mut commands, &plant_assets, &plant_registry)?;
+            Some((plant.position(), plant.plant_type(), entity))
+        })
+        .collect();
+    let plant_at = |position: Vec2, daisy: bool| {
+        plants
+            .iter()
+            .find(|(p, plant_type, _)| {
+                p.distance(position) < 1. && (!daisy || *plant_type == PlantType::Daisy)
+            })
+            .map(|(.., entity)| *entity)
+    };
+    for enemy in &save.enemies {
+        enemy.spawn(&mut commands, &enemy_registry, |p| plant_at(p, false));
+    }
+    *daisy_chains = DaisyChains::load(&save.daisy_chains, |p| plant_at(p, true));
+    for parcel in &save.parcels {
+        parcel.spawn(&mut commands, &player_assets);
+    }
+    for water in &save.water_in_flight {
+        water.spawn(&mut commands);
+    }
+    // A farmer who was part way through a throw finishes it before taking the next off the queue
+    *throw_queue = ThrowQueue::load(&save.throw_queue);
+    for mut player_animation in &mut q_player_animation {
+        *player_animation = PlayerAnimation::load(&save.player_animation);
+    }
+    *rng = GameRng::load(&save.rng);
+    *tick = save.tick;
+    for seed in &save.seeds {
+        seed.spawn(&mut commands, &seed_assets);
+    }
+    for mut inventory in &mut q_inventory {
+        *inventory = Inventory::load(&save.inventory);
+    }
+    for order in &save.orders {
+        order.spawn(&mut commands);
+    }
+
+    commands.remove_resource::<PendingSave>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::enemy::EnemyKind;
+    use crate::game::sim::Sim;
+
+    /// The centre of the tile containing (100, 0).
+    const TILE: Vec2 = Vec2::new(128., 64.);
+
+    /// The save with everything in a fixed order, and floats rounded past the precision lost in
+    /// turning seconds into timers and back.
+    fn normalized(mut save: SaveGame) -> String {
+        fn sort<T: std::fmt::Debug>(items: &mut [T]) {
+            items.sort_by_cached_key(|item| format!("{:?}", item));
+        }
+        sort(&mut save.plants);
+        sort(&mut save.enemies);
+        sort(&mut save.seeds);
+        sort(&mut save.inventory);
+        sort(&mut save.orders);
+        sort(&mut save.daisy_chains);
+        sort(&mut save.parcels);
+        sort(&mut save.water_in_flight);
+
+        let text = format!("{:?}", save);
+        let mut rounded = String::new();
+        let mut number = String::new();
+        for c in text.chars().chain(std::iter::once(' ')) {
+            if c.is_ascii_digit() || c == '.' || c == '-' || (c == 'e' && !number.is_empty()) {
+                number.push(c);
+                continue;
+            }
+            match number.parse::<f32>() {
+                Ok(value) if number.contains('.') => rounded.push_str(&format!("{:.3}", value)),
+                _ => rounded.push_str(&number),
+            }
+            number.clear();
+            rounded.push(c);
+        }
+        rounded
+    }
+
+    #[test]
+    fn loading_a_save_restores_everything_in_it() {
+        let mut sim = Sim::new();
+        sim.order(PlantType::Daisy, 2, false);
+        sim.click(Vec2::new(100., 0.));
+        sim.sown_at(TILE);
+        sim.spawn_enemy(EnemyKind::Rat, TILE + Vec2::new(200., 0.));
+        sim.advance(0.5);
+        // Catch a seed, a bucket of water and a queued throw in the air
+        sim.click(Vec2::new(-100., -150.));
+        sim.click(Vec2::new(-100., 150.));
+        sim.water(TILE);
+        sim.advance(0.1);
+        let save = sim.save();
+        assert!(!save.seeds.is_empty());
+        assert!(!save.water_in_flight.is_empty());
+        assert!(!save.orders.is_empty());
+        assert!(!save.transactions.is_empty());
+        assert!(!save.enemies.is_empty());
+
+        let mut loaded = Sim::new();
+        loaded.load(save.clone());
+        assert_eq!(normalized(loaded.save()), normalized(save));
+    }
+}
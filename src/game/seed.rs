@@ -1,16 +1,38 @@
+//! Seeds flying across the farm.
+//!
+//! Each hop of a seed's path is a parabolic arc, taking longer and flying higher the further it
+//! goes. Enemies can snatch a seed out of the air as it comes down at the end of a hop.
+
 use crate::asset_tracking::LoadResource;
 use crate::game::despawn::DespawnOnRestart;
+use crate::game::enemy::Enemy;
+use crate::game::farm::FarmGrid;
 use crate::game::plant::{PlantType, SowPlantEvent};
 use crate::game::player::ThrowSeedEvent;
-use crate::{OnPauseSystems, PausableSystems};
+use crate::theme::palette::SEED_SHADOW;
+use crate::{AppSystems, DrawSystems, OnPauseSystems, PausableSystems, Pause};
 use avian2d::prelude::{LinearVelocity, RigidBody};
 use bevy::image::{ImageLoaderSettings, ImageSampler};
 use bevy::prelude::*;
+use bevy_vector_shapes::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
+use std::time::Duration;
 
 const SEED_Z_LAYER: f32 = 2.0;
+/// How fast a seed covers the ground, on average.
 const SEED_MOVE_SPEED: f32 = 300.;
-const SEED_POINT_EPLISON: f32 = 5.0;
+/// Even a tiny hop takes this long.
+const SEED_MIN_FLIGHT_S: f32 = 0.2;
+/// How high the top of an arc is, as a fraction of how far the hop goes.
+const SEED_ARC_HEIGHT_RATIO: f32 = 0.35;
+/// Seeds lower than this can be caught by enemies.
+const SEED_CATCH_HEIGHT_PX: f32 = 20.;
+/// How far through a hop a seed has to be before it can be caught, so it's only caught coming down.
+const SEED_CATCH_MIN_FRACTION: f32 = 0.5;
+/// How close an enemy has to be to the seed's shadow to catch it.
+const SEED_CATCH_RADIUS_PX: f32 = 30.;
+const SEED_SHADOW_RADIUS_PX: f32 = 8.;
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<Seed>();
@@ -18,13 +40,19 @@ pub(super) fn plugin(app: &mut App) {
     app.register_type::<SeedAssets>();
     app.load_resource::<SeedAssets>();
 
+    app.add_event::<SeedInterceptedEvent>();
+    app.add_event::<SeedMissedEvent>();
+
     app.add_systems(Update, freeze_seeds.in_set(OnPauseSystems));
     app.add_systems(
-        Update,
-        (create_seeds, move_seeds)
+        FixedUpdate,
+        (create_seeds, move_seeds, intercept_low_seeds)
+            .chain()
             .run_if(resource_exists::<SeedAssets>)
             .in_set(PausableSystems),
     );
+    app.add_systems(Update, lift_seed_sprites.in_set(AppSystems::Update));
+    app.add_systems(Update, draw_seed_shadows.in_set(DrawSystems));
 }
 
 pub fn seed(
@@ -33,19 +61,27 @@ pub fn seed(
     path: SeedPath,
     origin: Vec3,
 ) -> impl Bundle {
+    let to = path.path.first().map_or(origin.xy(), |p| p.as_vec2());
     (
         Name::new("Seed"),
         Seed,
         DespawnOnRestart,
         plant_type,
+        SeedFlight::new(origin.xy(), to),
         path,
+        // The seed's transform follows its shadow along the ground, and the sprite flies above it
         Transform::from_translation(origin),
-        Sprite {
-            image: seed_assets.seed.clone(),
-            ..default()
-        },
+        Visibility::default(),
         RigidBody::Kinematic,
         LinearVelocity::ZERO,
+        children![(
+            Name::new("Seed Sprite"),
+            SeedSprite,
+            Sprite {
+                image: seed_assets.seed.clone(),
+                ..default()
+            },
+        )],
     )
 }
 
@@ -58,6 +94,167 @@ pub struct SeedPath {
     path: Vec<IVec2>,
 }
 
+/// The sprite of anything thrown in an arc, lifted off the ground by the height of its flight.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct SeedSprite;
+
+/// The hop a seed (or anything else thrown) is flying now, from where it was thrown to the next
+/// point on its path.
+#[derive(Component, Debug, Clone, PartialEq, Default)]
+pub struct SeedFlight {
+    to: Vec2,
+    peak_height: f32,
+    timer: Timer,
+}
+
+impl SeedFlight {
+    pub fn new(from: Vec2, to: Vec2) -> Self {
+        let distance = from.distance(to);
+        let duration = (distance / SEED_MOVE_SPEED).max(SEED_MIN_FLIGHT_S);
+        Self {
+            to,
+            peak_height: distance * SEED_ARC_HEIGHT_RATIO,
+            timer: Timer::from_seconds(duration, TimerMode::Once),
+        }
+    }
+
+    /// How high the seed is above the ground, `overstep` after the last tick.
+    pub fn height(&self, overstep: Duration) -> f32 {
+        let duration = self.timer.duration().as_secs_f32();
+        let t = ((self.timer.elapsed() + overstep).as_secs_f32() / duration).clamp(0., 1.);
+        4. * self.peak_height * t * (1. - t)
+    }
+
+    /// Fly on for `delta`, returning whether the hop is over.
+    pub fn tick(&mut self, delta: Duration) -> bool {
+        self.timer.tick(delta);
+        self.timer.finished()
+    }
+
+    /// The velocity that gets from `position` to the end of the hop just as the arc comes down,
+    /// without overshooting it in a tick of `delta`.
+    pub fn velocity(&self, position: Vec2, delta: Duration) -> Vec2 {
+        let secs = self.timer.remaining_secs().max(delta.as_secs_f32());
+        if secs <= 0. {
+            return Vec2::ZERO;
+        }
+        (self.to - position) / secs
+    }
+
+    pub fn save(&self) -> SavedFlight {
+        SavedFlight {
+            to: self.to.into(),
+            peak_height: self.peak_height,
+            elapsed_s: self.timer.elapsed_secs(),
+            duration_s: self.timer.duration().as_secs_f32(),
+        }
+    }
+
+    /// The enemy close enough to catch whatever is flying at `position`, if it's flying low.
+    pub fn catcher<'a>(
+        &self,
+        position: Vec2,
+        mut enemies: impl Iterator<Item = (Entity, &'a Transform)>,
+    ) -> Option<Entity> {
+        if self.timer.finished()
+            || self.timer.fraction() < SEED_CATCH_MIN_FRACTION
+            || self.height(Duration::ZERO) > SEED_CATCH_HEIGHT_PX
+        {
+            return None;
+        }
+        enemies
+            .find(|(_, enemy_transform)| {
+                enemy_transform.translation.xy().distance(position) < SEED_CATCH_RADIUS_PX
+            })
+            .map(|(enemy, _)| enemy)
+    }
+}
+
+/// Sent when an enemy catches a seed out of the air.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct SeedInterceptedEvent {
+    pub enemy: Entity,
+    pub position: Vec2,
+    pub seed_type: PlantType,
+}
+
+/// Sent when a seed lands where nothing can be sown, e.g. on a tile that filled up while it flew.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct SeedMissedEvent {
+    pub position: Vec2,
+    pub seed_type: PlantType,
+}
+
+impl SeedPath {
+    /// Where the seed will finally land.
+    pub fn destination(&self) -> Option<IVec2> {
+        self.path.last().copied()
+    }
+}
+
+/// How far through its hop something thrown was when the game was saved.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SavedFlight {
+    to: (f32, f32),
+    peak_height: f32,
+    elapsed_s: f32,
+    duration_s: f32,
+}
+
+impl SavedFlight {
+    pub fn load(&self) -> SeedFlight {
+        let mut timer = Timer::from_seconds(self.duration_s, TimerMode::Once);
+        timer.set_elapsed(Duration::from_secs_f32(self.elapsed_s));
+        SeedFlight {
+            to: self.to.into(),
+            peak_height: self.peak_height,
+            timer,
+        }
+    }
+}
+
+/// A seed in flight as it was when the game was saved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedSeed {
+    plant_type: PlantType,
+    position: (f32, f32),
+    /// The points the seed has still to pass through.
+    path: Vec<(i32, i32)>,
+    flight: SavedFlight,
+}
+
+impl SavedSeed {
+    pub fn new(
+        plant_type: PlantType,
+        transform: &Transform,
+        seed_path: &SeedPath,
+        flight: &SeedFlight,
+    ) -> Self {
+        Self {
+            plant_type,
+            position: transform.translation.xy().into(),
+            path: seed_path.path.iter().map(|p| (*p).into()).collect(),
+            flight: flight.save(),
+        }
+    }
+
+    /// Respawn the seed, still following its path.
+    pub fn spawn(&self, commands: &mut Commands, seed_assets: &SeedAssets) {
+        commands
+            .spawn(seed(
+                seed_assets,
+                self.plant_type,
+                SeedPath {
+                    path: self.path.iter().map(|p| IVec2::from(*p)).collect(),
+                },
+                Vec2::from(self.position).extend(SEED_Z_LAYER),
+            ))
+            // Carry on the hop it was part way through, rather than starting a fresh one
+            .insert(self.flight.load());
+    }
+}
+
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct SeedAssets {
@@ -105,7 +302,7 @@ fn create_seeds(
     }
 }
 
-fn freeze_seeds(mut q_seeds: Query<&mut LinearVelocity, With<Seed>>) {
+fn freeze_seeds(mut q_seeds: Query<&mut LinearVelocity, With<SeedFlight>>) {
     for mut vel in q_seeds.iter_mut() {
         *vel = LinearVelocity::ZERO;
     }
@@ -118,6 +315,7 @@ fn move_seeds(
             Entity,
             &Transform,
             &mut LinearVelocity,
+            &mut SeedFlight,
             &SeedPath,
             &PlantType,
         ),
@@ -125,46 +323,140 @@ fn move_seeds(
     >,
     mut sow_plants_events: EventWriter<SowPlantEvent>,
     mut throw_seed_events: EventWriter<ThrowSeedEvent>,
+    mut seed_missed_events: EventWriter<SeedMissedEvent>,
+    farm_grid: Res<FarmGrid>,
+    time: Res<Time>,
 ) {
-    for (seed, seed_transform, mut vel, seed_path, seed_type) in q_seeds.iter_mut() {
-        if seed_path.path.is_empty() {
+    for (seed, seed_transform, mut vel, mut flight, seed_path, seed_type) in q_seeds.iter_mut() {
+        if !flight.tick(time.delta()) {
+            *vel = LinearVelocity(flight.velocity(seed_transform.translation.xy(), time.delta()));
+            continue;
+        }
+
+        commands.entity(seed).try_despawn();
+        let Some(target) = seed_path.path.first() else {
             info!(
                 "Seed has no path, gonna plant at {:?}",
                 seed_transform.translation.xy()
             );
-            commands.entity(seed).despawn();
             sow_plants_events.write(SowPlantEvent {
                 position: seed_transform.translation.xy(),
                 seed_type: *seed_type,
             });
-            return;
+            continue;
+        };
+
+        let landed = FarmGrid::tile_at(target.as_vec2());
+        if seed_path.path.len() == 1 && landed.is_none_or(|tile| farm_grid.is_occupied(tile)) {
+            info!("Seed reached point {:?}, but can't be sown there", target);
+            seed_missed_events.write(SeedMissedEvent {
+                position: target.as_vec2(),
+                seed_type: *seed_type,
+            });
+        } else if seed_path.path.len() == 1 {
+            info!("Seed reached point {:?}, gonna plant now", target);
+            sow_plants_events.write(SowPlantEvent {
+                position: target.as_vec2(),
+                seed_type: *seed_type,
+            });
+        } else {
+            info!(
+                "Seed reached point {:?}, going to next point {:?}",
+                target, seed_path.path[1],
+            );
+
+            throw_seed_events.write(ThrowSeedEvent {
+                from_player: false,
+                path: seed_path.path.clone(),
+                seed_type: *seed_type,
+            });
         }
-        let target = seed_path.path.first().unwrap().as_vec2();
-        let vec_to_target = target - seed_transform.translation.xy();
-        let dist_from_target = vec_to_target.length();
-        if dist_from_target < SEED_POINT_EPLISON {
-            commands.entity(seed).despawn();
-
-            if seed_path.path.len() == 1 {
-                info!("Seed reached point {:?}, gonna plant now", target);
-                sow_plants_events.write(SowPlantEvent {
-                    position: seed_transform.translation.xy(),
-                    seed_type: *seed_type,
-                });
-            } else {
-                info!(
-                    "Seed reached point {:?}, going to next point {:?}",
-                    target, seed_path.path[1],
-                );
-
-                throw_seed_events.write(ThrowSeedEvent {
-                    from_player: false,
-                    path: seed_path.path.clone(),
-                    seed_type: *seed_type,
-                });
+    }
+}
+
+/// Let enemies catch seeds that pass low over them.
+fn intercept_low_seeds(
+    mut commands: Commands,
+    q_seeds: Query<(Entity, &Transform, &SeedFlight, &PlantType), With<Seed>>,
+    q_enemies: Query<(Entity, &Transform), With<Enemy>>,
+    mut seed_intercepted_events: EventWriter<SeedInterceptedEvent>,
+) {
+    for (seed, seed_transform, flight, seed_type) in q_seeds.iter() {
+        let position = seed_transform.translation.xy();
+        let Some(enemy) = flight.catcher(position, q_enemies.iter()) else {
+            continue;
+        };
+
+        info!(
+            "{:?} caught a {:?} seed at {:?}",
+            enemy, seed_type, position
+        );
+        commands.entity(seed).try_despawn();
+        seed_intercepted_events.write(SeedInterceptedEvent {
+            enemy,
+            position,
+            seed_type: *seed_type,
+        });
+    }
+}
+
+/// Raise each thrown sprite to the height of its arc, carrying on smoothly between ticks.
+fn lift_seed_sprites(
+    q_seeds: Query<(&SeedFlight, &Children)>,
+    mut q_sprites: Query<&mut Transform, With<SeedSprite>>,
+    fixed_time: Res<Time<Fixed>>,
+    pause: Res<State<Pause>>,
+) {
+    let overstep = if pause.get().0 {
+        Duration::ZERO
+    } else {
+        fixed_time.overstep()
+    };
+    for (flight, children) in q_seeds.iter() {
+        for &child in children {
+            if let Ok(mut transform) = q_sprites.get_mut(child) {
+                transform.translation.y = flight.height(overstep);
             }
-        } else {
-            *vel = LinearVelocity(SEED_MOVE_SPEED * vec_to_target.normalize());
         }
     }
 }
+
+fn draw_seed_shadows(mut painter: ShapePainter, q_seeds: Query<(&Transform, &SeedFlight)>) {
+    painter.hollow = false;
+    painter.color = SEED_SHADOW;
+    for (transform, flight) in q_seeds.iter() {
+        // Higher seeds cast smaller shadows
+        let scale = 1. / (1. + flight.height(Duration::ZERO) / 100.);
+        painter.transform =
+            Transform::from_translation(transform.translation.with_z(SEED_Z_LAYER - 0.1))
+                .with_scale(Vec3::new(scale, scale * 0.5, 1.));
+        painter.circle(SEED_SHADOW_RADIUS_PX);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::enemy::EnemyKind;
+    use crate::game::sim::Sim;
+
+    /// The centre of the tile containing (100, 0).
+    const TILE: Vec2 = Vec2::new(128., 64.);
+
+    #[test]
+    fn rat_at_the_landing_tile_catches_a_seed_as_it_comes_down() {
+        let mut sim = Sim::new();
+        sim.spawn_enemy(EnemyKind::Rat, TILE + Vec2::new(0., -10.));
+        sim.click(Vec2::new(100., 0.));
+        sim.advance(1.);
+        assert_eq!(sim.plant_at(TILE), None);
+    }
+
+    #[test]
+    fn rat_at_the_farmers_feet_lets_a_seed_go_up_past_it() {
+        let mut sim = Sim::new();
+        sim.spawn_enemy(EnemyKind::Rat, Vec2::new(20., 0.));
+        sim.click(Vec2::new(100., 0.));
+        sim.sown_at(TILE);
+    }
+}
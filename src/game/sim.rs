@@ -0,0 +1,414 @@
+//! A headless copy of the game for integration tests.
+//!
+//! [`Sim`] runs the game plugins in a `MinimalPlugins` app with no window, audio or renderer.
+//! Assets are loaded from disk, input is injected as events and time advances in fixed steps.
+
+use crate::asset_tracking::{self, ResourceHandles};
+use crate::game::daisy_chain::DaisyChainParcel;
+use crate::game::enemy::{Enemy, EnemyKind, enemy};
+use crate::game::enemy_registry::EnemyRegistry;
+use crate::game::farm::{BankAccount, SowRejectedEvent, SowRejection};
+use crate::game::fire::{Burning, IgnitePlantEvent};
+use crate::game::health::Health;
+use crate::game::inventory::Inventory;
+use crate::game::ledger::Transaction;
+use crate::game::level::spawn_level;
+use crate::game::mail_order::OrderRejectedEvent;
+use crate::game::plant::{GrowthTimer, Plant, PlantType, SeedSelection, SowPlantEvent};
+use crate::game::player::Player;
+use crate::game::player_animation::{PlayerAnimation, PlayerAnimationState};
+use crate::game::replay::{Replay, ReplayInput, ReplayPlayback, ReplayRecorder, restart_replay};
+use crate::game::rng::{GameRng, NextSeed};
+use crate::game::save::{PendingSave, SaveGame, capture_save, load_pending_save};
+use crate::game::wave::WaveScript;
+use crate::{DrawSystems, configure_schedules};
+use bevy::asset::AssetMetaCheck;
+use bevy::audio::AudioLoader;
+use bevy::ecs::system::RunSystemOnce;
+use bevy::image::TextureAtlasPlugin;
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use bevy::scene::ScenePlugin;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy_cobweb::prelude::*;
+use std::time::{Duration, Instant};
+
+/// How much game time passes in each update: exactly one fixed tick at Bevy's default 64 Hz.
+const STEP: Duration = Duration::from_micros(15_625);
+/// Tests always play the same game.
+const SEED: u64 = 1;
+/// How long to wait for assets to load before giving up.
+const LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long a seed may fly before it should have landed.
+const LANDING_TIMEOUT_STEPS: u32 = 5 * 60;
+/// How long to wait for something to happen before giving up.
+const WAIT_TIMEOUT_STEPS: u32 = 20 * 64;
+
+/// A plant as seen by a test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimPlant {
+    pub plant_type: PlantType,
+    pub position: Vec2,
+    pub health: i32,
+    pub grown: bool,
+    pub burning: bool,
+}
+
+/// A running game without a window.
+pub struct Sim {
+    app: App,
+}
+
+impl Sim {
+    /// Build the game, wait for its assets and spawn the level.
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            AssetPlugin {
+                meta_check: AssetMetaCheck::Never,
+                ..default()
+            },
+            ImagePlugin::default_nearest(),
+            TextureAtlasPlugin,
+            InputPlugin,
+            StatesPlugin,
+            ScenePlugin,
+            ReactPlugin,
+        ));
+        // Register the asset types that the render and audio plugins would
+        app.init_resource::<Assets<Mesh>>();
+        app.init_asset::<AudioSource>()
+            .init_asset_loader::<AudioLoader>();
+
+        app.add_plugins((asset_tracking::plugin, super::simulation_plugin));
+        configure_schedules(&mut app);
+        app.configure_sets(Update, DrawSystems.run_if(|| false));
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(STEP));
+        app.insert_resource(GameRng::new(SEED));
+        app.insert_resource(NextSeed(SEED));
+
+        app.finish();
+        app.cleanup();
+
+        let started = Instant::now();
+        while !app.world().resource::<ResourceHandles>().is_all_done() {
+            assert!(started.elapsed() < LOAD_TIMEOUT, "Timed out loading assets");
+            app.update();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        app.world_mut()
+            .run_system_once(spawn_level)
+            .expect("Could not spawn the level");
+        let seed_selection = app.world_mut().spawn(Name::new("Seed Selection")).id();
+        app.world_mut()
+            .commands()
+            .react()
+            .insert(seed_selection, SeedSelection::default());
+        app.world_mut().flush();
+        app.world_mut()
+            .run_system_once(restart_replay)
+            .expect("Could not start recording");
+        app.update();
+
+        Self { app }
+    }
+
+    /// Feed a recorded game into this one in place of live input.
+    pub fn play(&mut self, replay: Replay) {
+        let world = self.app.world_mut();
+        world.insert_resource(ReplayPlayback::new(replay));
+        world
+            .run_system_once(restart_replay)
+            .expect("Could not start playback");
+    }
+
+    /// Everything that has been played so far.
+    pub fn recording(&self) -> Replay {
+        self.app
+            .world()
+            .resource::<ReplayRecorder>()
+            .0
+            .clone()
+            .expect("Not recording")
+    }
+
+    /// Everything a save of the game would keep.
+    pub fn save(&mut self) -> SaveGame {
+        self.app
+            .world_mut()
+            .run_system_once(capture_save)
+            .expect("Could not save")
+            .expect("No game to save")
+    }
+
+    /// Pick up a saved game, as the gameplay screen does on continuing.
+    pub fn load(&mut self, save: SaveGame) {
+        let world = self.app.world_mut();
+        world.insert_resource(PendingSave(save));
+        world
+            .run_system_once(load_pending_save)
+            .expect("Could not load");
+    }
+
+    /// Run the game for one step.
+    pub fn step(&mut self) {
+        self.app.update();
+    }
+
+    /// Run the game for (at least) `seconds` of game time.
+    pub fn advance(&mut self, seconds: f32) {
+        let steps = (seconds / STEP.as_secs_f32()).ceil() as u32;
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    /// Run the game until `condition` holds.
+    pub fn advance_until(&mut self, what: &str, condition: impl Fn(&mut Self) -> bool) {
+        for _ in 0..WAIT_TIMEOUT_STEPS {
+            if condition(self) {
+                return;
+            }
+            self.step();
+        }
+        panic!("Timed out waiting until {}", what);
+    }
+
+    /// Stop enemy waves coming, for tests that take longer than the first break.
+    pub fn hold_waves(&mut self) {
+        self.app.world_mut().remove_resource::<WaveScript>();
+    }
+
+    /// Left click on the farm at a world position.
+    pub fn click(&mut self, position: Vec2) {
+        self.app
+            .world_mut()
+            .send_event(ReplayInput::Click(position.into()));
+    }
+
+    /// Right click on the farm at a world position.
+    pub fn water(&mut self, position: Vec2) {
+        self.app
+            .world_mut()
+            .send_event(ReplayInput::Water(position.into()));
+    }
+
+    /// Choose the seed that the next click throws.
+    pub fn select_seed(&mut self, plant_type: PlantType) {
+        self.app
+            .world_mut()
+            .send_event(ReplayInput::SelectSeed(plant_type));
+    }
+
+    /// Pick the seed for the next new game, as the settings menu does.
+    pub fn set_next_seed(&mut self, seed: u64) {
+        self.app.world_mut().insert_resource(NextSeed(seed));
+    }
+
+    /// The seed the running game draws from.
+    pub fn seed(&self) -> u64 {
+        self.app.world().resource::<GameRng>().seed()
+    }
+
+    /// Press the restart button.
+    pub fn restart(&mut self) {
+        self.app.world_mut().send_event(ReplayInput::Restart);
+    }
+
+    /// Order seeds to the mailbox.
+    pub fn order(&mut self, plant_type: PlantType, quantity: u32, express: bool) {
+        self.app.world_mut().send_event(ReplayInput::OrderSeeds {
+            plant_type,
+            quantity,
+            express,
+        });
+    }
+
+    /// How many seeds of a plant are in stock.
+    pub fn stock(&mut self, plant_type: PlantType) -> u32 {
+        let world = self.app.world_mut();
+        world
+            .query_filtered::<&Inventory, With<Player>>()
+            .single(world)
+            .map_or(0, |inventory| inventory.count(plant_type))
+    }
+
+    /// Replace whatever seeds the farmer has to hand.
+    pub fn set_stock(&mut self, seeds: &[(PlantType, u32)]) {
+        let world = self.app.world_mut();
+        let mut q_inventory = world.query_filtered::<&mut Inventory, With<Player>>();
+        for mut inventory in q_inventory.iter_mut(world) {
+            *inventory = Inventory::load(seeds);
+        }
+    }
+
+    /// Whether the game has been lost.
+    pub fn lost(&mut self) -> bool {
+        let world = self.app.world_mut();
+        world
+            .query::<&Name>()
+            .iter(world)
+            .any(|name| name.as_str() == "GameOverText")
+    }
+
+    /// How many end of game screens are showing.
+    pub fn end_game_displays(&mut self) -> usize {
+        let world = self.app.world_mut();
+        world
+            .query::<&Name>()
+            .iter(world)
+            .filter(|name| matches!(name.as_str(), "GameOverText" | "WinGameText"))
+            .count()
+    }
+
+    /// Spawn an enemy straight onto the field, outside of any wave.
+    pub fn spawn_enemy(&mut self, kind: EnemyKind, position: Vec2) -> Entity {
+        self.app
+            .world_mut()
+            .resource_scope(|world, enemy_registry: Mut<EnemyRegistry>| {
+                let definition = enemy_registry
+                    .get(kind)
+                    .unwrap_or_else(|| panic!("No {} in the bestiary", kind));
+                world.spawn(enemy(position.extend(1.), definition)).id()
+            })
+    }
+
+    /// The current health of an entity, or `None` once it's gone.
+    pub fn health(&self, entity: Entity) -> Option<i32> {
+        self.app
+            .world()
+            .get::<Health>(entity)
+            .map(|health| health.current())
+    }
+
+    /// Every plant on the farm.
+    pub fn plants(&mut self) -> Vec<SimPlant> {
+        let world = self.app.world_mut();
+        let mut q_plants =
+            world.query::<(&Plant, &Transform, &Health, Has<GrowthTimer>, Has<Burning>)>();
+        q_plants
+            .iter(world)
+            .map(|(plant, transform, health, growing, burning)| SimPlant {
+                plant_type: plant.plant_type(),
+                position: transform.translation.xy(),
+                health: health.current(),
+                grown: !growing,
+                burning,
+            })
+            .collect()
+    }
+
+    /// The plant on the tile centred at `position`, if any.
+    pub fn plant_at(&mut self, position: Vec2) -> Option<SimPlant> {
+        self.plants()
+            .into_iter()
+            .find(|plant| plant.position.distance(position) < 1.)
+    }
+
+    /// Sow a plant straight onto the tile centred at `position`, as if a seed had landed there.
+    pub fn sow(&mut self, plant_type: PlantType, position: Vec2) {
+        self.app.world_mut().send_event(SowPlantEvent {
+            position,
+            seed_type: plant_type,
+        });
+    }
+
+    /// Set the plant on the tile centred at `position` alight.
+    pub fn ignite(&mut self, position: Vec2) {
+        let world = self.app.world_mut();
+        let plant_entity = world
+            .query_filtered::<(Entity, &Transform), With<Plant>>()
+            .iter(world)
+            .find(|(_, transform)| transform.translation.xy().distance(position) < 1.)
+            .map(|(entity, _)| entity)
+            .unwrap_or_else(|| panic!("Nothing to ignite at {:?}", position));
+        world.send_event(IgnitePlantEvent { plant_entity });
+    }
+
+    /// Wait for a thrown seed to land on the tile centred at `position`.
+    pub fn sown_at(&mut self, position: Vec2) -> SimPlant {
+        for _ in 0..LANDING_TIMEOUT_STEPS {
+            if let Some(plant) = self.plant_at(position) {
+                return plant;
+            }
+            self.step();
+        }
+        panic!("Nothing was sown at {:?}", position);
+    }
+
+    /// Why clicks were refused during the last step.
+    pub fn rejections(&self) -> Vec<SowRejection> {
+        self.app
+            .world()
+            .resource::<Events<SowRejectedEvent>>()
+            .iter_current_update_events()
+            .map(|ev| ev.rejection)
+            .collect()
+    }
+
+    /// The plant and quantity of each order refused during the last step.
+    pub fn order_rejections(&self) -> Vec<(PlantType, u32)> {
+        self.app
+            .world()
+            .resource::<Events<OrderRejectedEvent>>()
+            .iter_current_update_events()
+            .map(|ev| (ev.order.plant_type, ev.order.quantity))
+            .collect()
+    }
+
+    /// Put the bank balance at `balance`, keeping the ledger as it is.
+    pub fn set_balance(&mut self, balance: f32) {
+        let world = self.app.world_mut();
+        let mut q_bank_account = world.query::<&mut BankAccount>();
+        for mut account in q_bank_account.iter_mut(world) {
+            let transactions = account.transactions().to_vec();
+            account.load(balance, transactions);
+        }
+    }
+
+    pub fn balance(&mut self) -> f32 {
+        let world = self.app.world_mut();
+        world
+            .query::<&BankAccount>()
+            .single(world)
+            .map_or(0., |account| account.balance())
+    }
+
+    /// Every change to the bank balance so far.
+    pub fn transactions(&mut self) -> Vec<Transaction> {
+        let world = self.app.world_mut();
+        world
+            .query::<&BankAccount>()
+            .single(world)
+            .map_or(Vec::new(), |account| account.transactions().to_vec())
+    }
+
+    /// How many picked daisy chains are on their way to the farmer.
+    pub fn daisy_chains_in_flight(&mut self) -> usize {
+        let world = self.app.world_mut();
+        world
+            .query_filtered::<(), With<DaisyChainParcel>>()
+            .iter(world)
+            .count()
+    }
+
+    pub fn farmer_is_mailing(&mut self) -> bool {
+        let world = self.app.world_mut();
+        world
+            .query_filtered::<&PlayerAnimation, With<Player>>()
+            .single(world)
+            .is_ok_and(|animation| *animation.state() == PlayerAnimationState::Mailing)
+    }
+
+    pub fn enemy_count(&mut self) -> usize {
+        let world = self.app.world_mut();
+        world
+            .query_filtered::<(), With<Enemy>>()
+            .iter(world)
+            .count()
+    }
+}
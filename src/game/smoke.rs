@@ -2,6 +2,7 @@ use crate::PausableSystems;
 use crate::asset_tracking::LoadResource;
 use crate::game::despawn::DespawnOnRestart;
 use crate::game::lifespan::LifespanTimer;
+use crate::game::rng::GameRng;
 use avian2d::prelude::{LinearVelocity, RigidBody};
 use bevy::image::{ImageLoaderSettings, ImageSampler};
 use bevy::prelude::*;
@@ -18,7 +19,7 @@ pub(super) fn plugin(app: &mut App) {
     app.load_resource::<SmokeAssets>();
 
     app.add_systems(
-        Update,
+        FixedUpdate,
         spawn_smoke
             .run_if(resource_exists::<SmokeAssets>)
             .in_set(PausableSystems),
@@ -59,7 +60,7 @@ impl FromWorld for SmokeAssets {
     }
 }
 
-fn smoke(transform: Transform, smoke_assets: &SmokeAssets) -> impl Bundle {
+fn smoke(transform: Transform, smoke_assets: &SmokeAssets, rng: &mut GameRng) -> impl Bundle {
     (
         Name::new("Smoke"),
         DespawnOnRestart,
@@ -68,14 +69,13 @@ fn smoke(transform: Transform, smoke_assets: &SmokeAssets) -> impl Bundle {
         LinearVelocity(SMOKE_LIFT_SPEED * Vec2::Y),
         transform.with_scale(Vec3::splat(SMOKE_SCALE)),
         Sprite {
-            image: random_smoke_particle(smoke_assets),
+            image: random_smoke_particle(smoke_assets, rng),
             ..default()
         },
     )
 }
 
-fn random_smoke_particle(smoke_assets: &SmokeAssets) -> Handle<Image> {
-    let rng = &mut rand::thread_rng();
+fn random_smoke_particle(smoke_assets: &SmokeAssets, rng: &mut GameRng) -> Handle<Image> {
     smoke_assets.smoke_particles.choose(rng).unwrap().clone()
 }
 
@@ -83,9 +83,10 @@ fn spawn_smoke(
     mut commands: Commands,
     mut spawn_smoke_events: EventReader<SpawnSmokeEvent>,
     smoke_assets: Res<SmokeAssets>,
+    mut rng: ResMut<GameRng>,
 ) {
     for ev in spawn_smoke_events.read() {
         let transform = Transform::from_translation(ev.0);
-        commands.spawn(smoke(transform, &smoke_assets));
+        commands.spawn(smoke(transform, &smoke_assets, &mut rng));
     }
 }
@@ -0,0 +1,96 @@
+//! Fast-forwarding (or stopping) the game.
+//!
+//! The speed scales virtual time, which drives the fixed timestep that gameplay and physics
+//! run on. Pick a speed with the HUD buttons, or with Space (stop/go) and 1, 2 and 4.
+
+use crate::Pause;
+use crate::game::farm::RestartGameEvent;
+use crate::screens::Screen;
+use bevy::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<GameSpeed>();
+    app.init_resource::<GameSpeed>();
+
+    app.add_systems(
+        Update,
+        (
+            speed_hotkeys.run_if(in_state(Screen::Gameplay).and(in_state(Pause(false)))),
+            reset_speed.run_if(on_event::<RestartGameEvent>),
+            apply_speed.run_if(resource_changed::<GameSpeed>),
+        )
+            .chain(),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), reset_speed);
+}
+
+/// How fast game time passes compared to real time.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Resource)]
+pub enum GameSpeed {
+    Stopped,
+    #[default]
+    Normal,
+    Double,
+    Quadruple,
+}
+
+impl GameSpeed {
+    pub const ALL: [GameSpeed; 4] = [
+        GameSpeed::Stopped,
+        GameSpeed::Normal,
+        GameSpeed::Double,
+        GameSpeed::Quadruple,
+    ];
+
+    fn relative_speed(&self) -> f32 {
+        match self {
+            GameSpeed::Stopped => 0.,
+            GameSpeed::Normal => 1.,
+            GameSpeed::Double => 2.,
+            GameSpeed::Quadruple => 4.,
+        }
+    }
+}
+
+impl std::fmt::Display for GameSpeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameSpeed::Stopped => write!(f, "||"),
+            speed => write!(f, "{}x", speed.relative_speed()),
+        }
+    }
+}
+
+fn speed_hotkeys(input: Res<ButtonInput<KeyCode>>, mut speed: ResMut<GameSpeed>) {
+    if input.just_pressed(KeyCode::Space) {
+        *speed = if *speed == GameSpeed::Stopped {
+            GameSpeed::Normal
+        } else {
+            GameSpeed::Stopped
+        };
+    }
+    for (key, key_speed) in [
+        (KeyCode::Digit1, GameSpeed::Normal),
+        (KeyCode::Digit2, GameSpeed::Double),
+        (KeyCode::Digit4, GameSpeed::Quadruple),
+    ] {
+        if input.just_pressed(key) {
+            *speed = key_speed;
+        }
+    }
+}
+
+fn reset_speed(mut speed: ResMut<GameSpeed>) {
+    *speed = GameSpeed::Normal;
+}
+
+fn apply_speed(speed: Res<GameSpeed>, mut time: ResMut<Time<Virtual>>) {
+    info!("Game speed {}", *speed);
+    if *speed == GameSpeed::Stopped {
+        time.pause();
+    } else {
+        time.unpause();
+        time.set_relative_speed(speed.relative_speed());
+    }
+}
@@ -0,0 +1,155 @@
+//! The network of throws that seeds can be relayed along.
+//!
+//! The farmer can throw to any grown gnome in range, and each gnome can pass a seed on to any
+//! other within its own range. The network is rebuilt whenever a gnome grows or dies, and drawn
+//! as edges between everyone who can reach each other, with the unreachable tiles shaded out.
+
+use crate::game::farm::{FarmGrid, TILE_SIZE_PX};
+use crate::game::plant::{GNOME_THROW_RADIUS_PX, GrowthTimer, Plant, PlantType};
+use crate::game::player::{PLAYER_THROW_RADIUS_PX, Player};
+use crate::theme::palette::{GNOME_THROW_OUTLINE, THROW_NETWORK_UNREACHABLE};
+use crate::{AppSystems, DrawSystems, PausableSystems};
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+use bevy_vector_shapes::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ThrowNetwork>();
+    app.add_systems(
+        FixedUpdate,
+        rebuild_throw_network
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(Update, draw_throw_network.in_set(DrawSystems));
+}
+
+/// Who can throw to whom, and where on the farm seeds can be thrown at all.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct ThrowNetwork {
+    /// Pairs of the farmer or a gnome and a gnome they can throw to.
+    edges: Vec<(Vec2, Vec2)>,
+    /// Gnomes that a seed from the farmer can get to.
+    connected_gnomes: Vec<Vec2>,
+    /// Tiles that a seed from the farmer can land on.
+    reachable_tiles: HashSet<IVec2>,
+}
+
+impl ThrowNetwork {
+    pub fn build(farmer: Vec2, gnomes: &[Vec2]) -> Self {
+        let mut edges = Vec::new();
+        for (i, &a) in gnomes.iter().enumerate() {
+            if farmer.distance(a) <= PLAYER_THROW_RADIUS_PX {
+                edges.push((farmer, a));
+            }
+            for &b in &gnomes[i + 1..] {
+                if a.distance(b) <= GNOME_THROW_RADIUS_PX {
+                    edges.push((a, b));
+                }
+            }
+        }
+
+        // Spread out from the farmer along the edges
+        let mut connected_gnomes = Vec::new();
+        let mut frontier = vec![farmer];
+        while let Some(from) = frontier.pop() {
+            for &(a, b) in &edges {
+                let to = if a == from {
+                    b
+                } else if b == from {
+                    a
+                } else {
+                    continue;
+                };
+                if !connected_gnomes.contains(&to) {
+                    connected_gnomes.push(to);
+                    frontier.push(to);
+                }
+            }
+        }
+
+        let reachable_tiles = FarmGrid::tiles()
+            .filter(|&tile| {
+                let center = FarmGrid::tile_center(tile);
+                center.distance(farmer) <= PLAYER_THROW_RADIUS_PX
+                    || connected_gnomes
+                        .iter()
+                        .any(|gnome| center.distance(*gnome) <= GNOME_THROW_RADIUS_PX)
+            })
+            .collect();
+
+        Self {
+            edges,
+            connected_gnomes,
+            reachable_tiles,
+        }
+    }
+
+    /// Whether a seed from the farmer can be relayed to a tile.
+    pub fn can_reach(&self, tile: IVec2) -> bool {
+        self.reachable_tiles.contains(&tile)
+    }
+
+    /// Whether a gnome at `position` can be sent seeds from the farmer.
+    pub fn is_connected(&self, position: Vec2) -> bool {
+        self.connected_gnomes.contains(&position)
+    }
+}
+
+fn rebuild_throw_network(
+    mut network: ResMut<ThrowNetwork>,
+    mut grown: RemovedComponents<GrowthTimer>,
+    mut died: RemovedComponents<Plant>,
+    q_new_plants: Query<(), (Added<Plant>, Without<GrowthTimer>)>,
+    q_player: Query<Ref<Transform>, With<Player>>,
+    q_grown_plants: Query<(&Transform, &Plant), Without<GrowthTimer>>,
+) {
+    let Ok(player_transform) = q_player.single() else {
+        return;
+    };
+    // Drain both readers, so old removals don't trigger another rebuild
+    let changed = grown.read().count() + died.read().count() > 0;
+    if !changed && q_new_plants.is_empty() && !player_transform.is_changed() {
+        return;
+    }
+
+    let gnomes: Vec<Vec2> = q_grown_plants
+        .iter()
+        .filter(|(_, p)| p.plant_type() == PlantType::Gnome)
+        .map(|(t, _)| t.translation.xy())
+        .collect();
+    *network = ThrowNetwork::build(player_transform.translation.xy(), &gnomes);
+}
+
+fn draw_throw_network(mut painter: ShapePainter, network: Res<ThrowNetwork>) {
+    painter.transform = Transform::IDENTITY;
+    painter.thickness = 2.0;
+    painter.color = GNOME_THROW_OUTLINE;
+    for &(a, b) in &network.edges {
+        painter.line(a.extend(0.4), b.extend(0.4));
+    }
+
+    painter.hollow = false;
+    painter.color = THROW_NETWORK_UNREACHABLE;
+    for tile in FarmGrid::tiles().filter(|&tile| !network.can_reach(tile)) {
+        painter.transform = Transform::from_translation(FarmGrid::tile_center(tile).extend(0.3));
+        painter.rect(Vec2::splat(TILE_SIZE_PX));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gnomes_out_of_the_farmers_reach_are_only_connected_through_other_gnomes() {
+        let near = Vec2::new(200., 0.);
+        let relayed = Vec2::new(600., 0.);
+        let stranded = Vec2::new(-1200., 0.);
+        let network = ThrowNetwork::build(Vec2::ZERO, &[near, relayed, stranded]);
+        assert!(network.is_connected(near));
+        assert!(network.is_connected(relayed));
+        assert!(!network.is_connected(stranded));
+        assert_eq!(network.edges, vec![(Vec2::ZERO, near), (near, relayed)]);
+    }
+}
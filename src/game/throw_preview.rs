@@ -0,0 +1,240 @@
+//! Previewing where a seed would be thrown before clicking.
+//!
+//! While the cursor is over the farm, the relay route through gnomes is drawn from the farmer to
+//! the hovered tile: green if the seed could be thrown, red with the reason if it couldn't.
+//! Clicks that are refused anyway (e.g. taps, which can't hover) pop up the reason briefly.
+//! Tiles waiting in the throw queue are ringed, numbered in the order they'll be thrown.
+
+use crate::game::farm::{
+    FarmGrid, SowRejectedEvent, TILE_SIZE_PX, ThrowLimits, ThrowPlan, ThrowQueue, gnome_positions,
+    is_targeted, plan_throw,
+};
+use crate::game::inventory::Inventory;
+use crate::game::lifespan::LifespanTimer;
+use crate::game::plant::{GrowthTimer, Plant, SeedSelection};
+use crate::game::player::{Player, window_to_world};
+use crate::game::seed::{Seed, SeedPath};
+use crate::screens::Screen;
+use crate::theme::palette::{THROW_PREVIEW_INVALID, THROW_PREVIEW_VALID, THROW_QUEUED};
+use crate::{AppSystems, DrawSystems, PausableSystems};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_cobweb::prelude::Reactive;
+use bevy_vector_shapes::prelude::*;
+
+/// How far above the target the reason label floats.
+const LABEL_OFFSET_PX: f32 = 40.;
+/// How long the reason for a refused click stays up.
+const TOAST_SECS: f32 = 1.5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ThrowPreview>();
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_preview_label);
+    app.add_systems(
+        Update,
+        label_throw_queue
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), clear_preview);
+    app.add_systems(
+        Update,
+        (preview_throw, update_preview_label)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        Update,
+        toast_rejections
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update),
+    );
+    app.add_systems(
+        Update,
+        (draw_throw_preview, draw_throw_queue).in_set(DrawSystems),
+    );
+}
+
+/// The throw that clicking would make right now, if the cursor is over the farm.
+#[derive(Resource, Debug, Default)]
+struct ThrowPreview {
+    player_position: Vec2,
+    plan: Option<ThrowPlan>,
+}
+
+/// The text explaining why the previewed throw can't be made.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct ThrowPreviewLabel;
+
+/// The number on a tile waiting in the throw queue.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct QueuedThrowLabel;
+
+fn spawn_preview_label(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Throw Preview Label"),
+        ThrowPreviewLabel,
+        Text2d::default(),
+        TextColor(THROW_PREVIEW_INVALID),
+        Transform::from_xyz(0., 0., 2.),
+        Visibility::Hidden,
+        StateScoped(Screen::Gameplay),
+    ));
+}
+
+fn clear_preview(mut preview: ResMut<ThrowPreview>) {
+    *preview = ThrowPreview::default();
+}
+
+fn preview_throw(
+    mut preview: ResMut<ThrowPreview>,
+    q_windows: Query<&Window, With<PrimaryWindow>>,
+    q_camera: Query<(&Camera, &GlobalTransform)>,
+    q_player: Query<(&Transform, &Inventory), With<Player>>,
+    q_seeds: Query<&SeedPath, With<Seed>>,
+    throw_queue: Res<ThrowQueue>,
+    throw_limits: Res<ThrowLimits>,
+    q_seed_selection: Reactive<SeedSelection>,
+    q_grown_plants: Query<(&Transform, &Plant), Without<GrowthTimer>>,
+    farm_grid: Res<FarmGrid>,
+) {
+    preview.plan = None;
+    let (Ok(window), Ok((camera, camera_transform)), Ok((player_transform, inventory))) =
+        (q_windows.single(), q_camera.single(), q_player.single())
+    else {
+        return;
+    };
+    let Ok((_, seed_selection)) = q_seed_selection.get_single() else {
+        return;
+    };
+    let Some(tile) = window
+        .cursor_position()
+        .and_then(|p| window_to_world(p, camera, camera_transform))
+        .and_then(FarmGrid::tile_at)
+    else {
+        return;
+    };
+
+    let player_position = player_transform.translation.xy();
+    // Seeds already promised to queued throws aren't available for this one
+    let seed_type = seed_selection.seed_type().species();
+    let queued = throw_queue
+        .0
+        .iter()
+        .filter(|queued| queued.seed_type.species() == seed_type)
+        .count();
+    preview.player_position = player_position;
+    preview.plan = Some(plan_throw(
+        tile,
+        player_position.as_ivec2(),
+        gnome_positions(&q_grown_plants),
+        farm_grid.is_occupied(tile) || is_targeted(tile, &q_seeds) || throw_queue.contains(tile),
+        throw_queue.is_full(&throw_limits),
+        inventory.count(seed_type) as usize > queued,
+    ));
+}
+
+fn update_preview_label(
+    preview: Res<ThrowPreview>,
+    mut q_labels: Query<(&mut Text2d, &mut Transform, &mut Visibility), With<ThrowPreviewLabel>>,
+) {
+    for (mut text, mut transform, mut visibility) in q_labels.iter_mut() {
+        match preview
+            .plan
+            .as_ref()
+            .and_then(|p| p.problem.map(|r| (p.target, r)))
+        {
+            Some((target, reason)) => {
+                let reason = reason.to_string();
+                if text.0 != reason {
+                    text.0 = reason;
+                }
+                transform.translation =
+                    (target + Vec2::Y * LABEL_OFFSET_PX).extend(transform.translation.z);
+                visibility.set_if_neq(Visibility::Inherited);
+            }
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+}
+
+fn toast_rejections(
+    mut commands: Commands,
+    mut sow_rejected_events: EventReader<SowRejectedEvent>,
+) {
+    for ev in sow_rejected_events.read() {
+        commands.spawn((
+            Name::new("Sow Rejection Toast"),
+            Text2d::new(ev.rejection.to_string()),
+            TextColor(THROW_PREVIEW_INVALID),
+            Transform::from_translation((ev.position + Vec2::Y * LABEL_OFFSET_PX).extend(2.)),
+            LifespanTimer(Timer::from_seconds(TOAST_SECS, TimerMode::Once)),
+            StateScoped(Screen::Gameplay),
+        ));
+    }
+}
+
+fn draw_throw_preview(mut painter: ShapePainter, preview: Res<ThrowPreview>) {
+    let Some(plan) = &preview.plan else {
+        return;
+    };
+
+    painter.thickness = 3.0;
+    painter.color = if plan.problem.is_none() {
+        THROW_PREVIEW_VALID
+    } else {
+        THROW_PREVIEW_INVALID
+    };
+
+    // Without a route, just show what the farmer can't reach
+    let route: Vec<Vec2> = match &plan.path {
+        Some(path) => path.iter().map(|p| p.as_vec2()).collect(),
+        None => vec![preview.player_position, plan.target],
+    };
+    painter.transform = Transform::IDENTITY;
+    for hop in route.windows(2) {
+        painter.line(hop[0].extend(0.6), hop[1].extend(0.6));
+    }
+    for &point in route.iter().skip(1) {
+        painter.transform = Transform::from_translation(point.extend(0.6));
+        painter.circle(4.0);
+    }
+}
+
+fn draw_throw_queue(mut painter: ShapePainter, throw_queue: Res<ThrowQueue>) {
+    painter.hollow = true;
+    painter.thickness = 3.0;
+    painter.color = THROW_QUEUED;
+    for queued in throw_queue.0.iter() {
+        painter.transform =
+            Transform::from_translation(FarmGrid::tile_center(queued.tile).extend(0.6));
+        painter.circle(TILE_SIZE_PX / 4.);
+    }
+}
+
+/// Number the queued tiles, respawning the labels whenever the queue changes.
+fn label_throw_queue(
+    mut commands: Commands,
+    throw_queue: Res<ThrowQueue>,
+    q_labels: Query<Entity, With<QueuedThrowLabel>>,
+) {
+    if !throw_queue.is_changed() {
+        return;
+    }
+    for entity in q_labels.iter() {
+        commands.entity(entity).despawn();
+    }
+    for (i, queued) in throw_queue.0.iter().enumerate() {
+        commands.spawn((
+            Name::new("Queued Throw Label"),
+            QueuedThrowLabel,
+            Text2d::new((i + 1).to_string()),
+            TextColor(THROW_QUEUED),
+            Transform::from_translation(FarmGrid::tile_center(queued.tile).extend(2.)),
+            StateScoped(Screen::Gameplay),
+        ));
+    }
+}
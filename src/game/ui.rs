@@ -1,81 +1,164 @@
-use crate::game::farm::{BankAccount, BankAccountUpdateEvent, RestartGameEvent, WINNING_BALANCE};
-use crate::game::plant::{PINEAPPLE_DEFAULT_GENERATION, PlantType, SeedSelection};
+use crate::game::farm::{BankAccount, BankAccountUpdateEvent, WINNING_BALANCE};
+use crate::game::inventory::Inventory;
+use crate::game::mail_order::{BULK_ORDER_QUANTITY, MailOrder, order_price};
+use crate::game::plant::{PlantType, SeedSelection};
+use crate::game::plant_registry::PlantRegistry;
+use crate::game::player::Player;
+use crate::game::replay::ReplayInput;
+use crate::game::speed::GameSpeed;
+use crate::game::water::WaterTank;
+use crate::game::wave::{WaveClearedEvent, WaveStartedEvent, WaveState};
+use crate::theme::palette::{
+    SEED_BUTTON_BORDER, SEED_BUTTON_OUT_OF_STOCK, SEED_BUTTON_SELECTED_BORDER,
+    SEED_BUTTON_UNAFFORDABLE,
+};
 use bevy::prelude::*;
 use bevy_cobweb::prelude::*;
 use bevy_cobweb_ui::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins(CobwebUiPlugin).load("ui/hello.cobweb");
+    app.init_resource::<ExpressDelivery>();
 
-    app.add_systems(Update, update_ui);
+    app.add_systems(Update, (update_ui, update_wave_label, update_water_label));
+    app.add_systems(
+        Update,
+        (
+            (update_seed_buttons, update_order_buttons).run_if(resource_exists::<PlantRegistry>),
+            update_speed_buttons,
+            update_order_status,
+        ),
+    );
 }
 
 struct BalanceUpdate;
-struct CurrentSeedUpdate;
+struct WaveUpdate;
+struct WaterUpdate;
 
-pub fn build_ui(mut commands: Commands, mut scene_builder: SceneBuilder) {
+/// A button in the seed bar that selects a plant type.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct SeedButton(PlantType);
+
+/// A button in the HUD that sets the [`GameSpeed`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct SpeedButton(GameSpeed);
+
+/// The label of a [`SpeedButton`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct SpeedButtonLabel(GameSpeed);
+
+/// A button in the HUD that orders the current seed, or switches express delivery on or off.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum OrderButton {
+    Single,
+    Bulk,
+    Express,
+}
+
+impl OrderButton {
+    const ALL: [OrderButton; 3] = [OrderButton::Single, OrderButton::Bulk, OrderButton::Express];
+
+    fn quantity(&self) -> u32 {
+        match self {
+            OrderButton::Bulk => BULK_ORDER_QUANTITY,
+            _ => 1,
+        }
+    }
+}
+
+/// The label of an [`OrderButton`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct OrderButtonLabel(OrderButton);
+
+/// The text showing the current seed's stock and deliveries.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct OrderStatusLabel;
+
+/// Whether the order buttons pay extra for express delivery.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct ExpressDelivery(bool);
+
+/// The price and stock label of a [`SeedButton`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct SeedButtonLabel(PlantType);
+
+pub fn build_ui(
+    mut commands: Commands,
+    mut scene_builder: SceneBuilder,
+    plant_registry: Res<PlantRegistry>,
+) {
     commands
         .ui_root()
         .spawn_scene(("ui/hello.cobweb", "scene"), &mut scene_builder, |h| {
             h.insert_reactive(SeedSelection::default());
             let scene_entity = h.id();
 
-            h.edit("seeds::seed_button_daisy", |h| {
-                h.on_pressed(
-                    move |mut c: Commands, mut seed_selection: ReactiveMut<SeedSelection>| {
-                        seed_selection
-                            .get_mut(&mut c, scene_entity)?
-                            .set_seed_type(PlantType::Daisy);
-                        c.react().broadcast(CurrentSeedUpdate);
-                        OK
-                    },
-                );
-            });
-            h.edit("seeds::seed_button_pineapple", |h| {
-                h.on_pressed(
-                    move |mut c: Commands, mut seed_selection: ReactiveMut<SeedSelection>| {
-                        seed_selection
-                            .get_mut(&mut c, scene_entity)?
-                            .set_seed_type(PlantType::Pineapple(PINEAPPLE_DEFAULT_GENERATION));
-                        c.react().broadcast(CurrentSeedUpdate);
-                        OK
-                    },
-                );
+            h.edit("seeds", |h| {
+                for definition in plant_registry.plants() {
+                    let plant_type = definition.plant_type;
+                    let sprite = definition.sprite.clone();
+                    h.spawn_scene(("ui/hello.cobweb", "seed_button"), |h| {
+                        h.insert(SeedButton(plant_type));
+                        h.get("sprite").insert(ImageNode::new(sprite));
+                        h.get("text").insert(SeedButtonLabel(plant_type));
+                        h.on_pressed(move |mut inputs: EventWriter<ReplayInput>| {
+                            inputs.write(ReplayInput::SelectSeed(plant_type));
+                            OK
+                        });
+                    });
+                }
             });
-            h.edit("seeds::seed_button_dragonfruit", |h| {
-                h.on_pressed(
-                    move |mut c: Commands, mut seed_selection: ReactiveMut<SeedSelection>| {
-                        seed_selection
-                            .get_mut(&mut c, scene_entity)?
-                            .set_seed_type(PlantType::Dragonfruit);
-                        c.react().broadcast(CurrentSeedUpdate);
-                        OK
-                    },
-                );
+
+            h.edit("speed", |h| {
+                for speed in GameSpeed::ALL {
+                    h.spawn_scene(("ui/hello.cobweb", "speed_button"), |h| {
+                        h.insert(SpeedButton(speed));
+                        h.get("text").insert(SpeedButtonLabel(speed));
+                        h.on_pressed(move |mut game_speed: ResMut<GameSpeed>| {
+                            *game_speed = speed;
+                            OK
+                        });
+                    });
+                }
             });
-            h.edit("seeds::seed_button_gnome", |h| {
-                h.on_pressed(
-                    move |mut c: Commands, mut seed_selection: ReactiveMut<SeedSelection>| {
-                        seed_selection
-                            .get_mut(&mut c, scene_entity)?
-                            .set_seed_type(PlantType::Gnome);
-                        c.react().broadcast(CurrentSeedUpdate);
-                        OK
-                    },
-                );
+
+            h.edit("orders", |h| {
+                for button in OrderButton::ALL {
+                    h.spawn_scene(("ui/hello.cobweb", "order_button"), |h| {
+                        h.insert(button);
+                        h.get("text").insert(OrderButtonLabel(button));
+                        h.on_pressed(
+                            move |mut inputs: EventWriter<ReplayInput>,
+                                  mut express: ResMut<ExpressDelivery>,
+                                  q_seed_selection: Reactive<SeedSelection>| {
+                                if button == OrderButton::Express {
+                                    express.0 = !express.0;
+                                    return OK;
+                                }
+                                let (_, seed_selection) = q_seed_selection.single();
+                                inputs.write(ReplayInput::OrderSeeds {
+                                    plant_type: seed_selection.seed_type().species(),
+                                    quantity: button.quantity(),
+                                    express: express.0,
+                                });
+                                OK
+                            },
+                        );
+                    });
+                }
             });
 
+            h.get("order_status").insert(OrderStatusLabel);
+
             h.edit("reset_button", |h| {
-                h.on_pressed(
-                    move |mut restart_game_events: EventWriter<RestartGameEvent>| {
-                        restart_game_events.write_default();
-                        OK
-                    },
-                );
+                h.on_pressed(move |mut inputs: EventWriter<ReplayInput>| {
+                    inputs.write(ReplayInput::Restart);
+                    OK
+                });
             });
 
             h.get("current_seed").update_on(
-                broadcast::<CurrentSeedUpdate>(),
+                entity_mutation::<SeedSelection>(scene_entity),
                 move |id: TargetId,
                       mut editor: TextEditor,
                       q_seed_selection: Reactive<SeedSelection>| {
@@ -86,6 +169,29 @@ pub fn build_ui(mut commands: Commands, mut scene_builder: SceneBuilder) {
                 },
             );
 
+            h.get("wave").update_on(
+                broadcast::<WaveUpdate>(),
+                move |id: TargetId, mut editor: TextEditor, wave_state: Res<WaveState>| {
+                    write_text!(editor, *id, "{}", wave_state.summary());
+                },
+            );
+
+            h.get("water").update_on(
+                broadcast::<WaterUpdate>(),
+                move |id: TargetId, mut editor: TextEditor, q_tanks: Query<&WaterTank>| {
+                    let Ok(tank) = q_tanks.single() else {
+                        return;
+                    };
+                    write_text!(
+                        editor,
+                        *id,
+                        "Water (right click):\n{}/{} buckets",
+                        tank.buckets(),
+                        tank.capacity()
+                    );
+                },
+            );
+
             h.get("bank").update_on(
                 broadcast::<BalanceUpdate>(),
                 move |id: TargetId, mut editor: TextEditor, q_bank_account: Query<&BankAccount>| {
@@ -115,3 +221,198 @@ fn update_ui(
         commands.react().broadcast(BalanceUpdate);
     }
 }
+
+/// Refresh the wave label whenever its text would change.
+fn update_wave_label(
+    mut commands: Commands,
+    wave_state: Res<WaveState>,
+    mut wave_started_events: EventReader<WaveStartedEvent>,
+    mut wave_cleared_events: EventReader<WaveClearedEvent>,
+    mut last_summary: Local<String>,
+) {
+    for ev in wave_started_events.read() {
+        info!("Update UI for start of wave {}", ev.wave_number);
+    }
+    for ev in wave_cleared_events.read() {
+        info!("Update UI for end of wave {}", ev.wave_number);
+    }
+
+    let summary = wave_state.summary();
+    if *last_summary != summary {
+        *last_summary = summary;
+        commands.react().broadcast(WaveUpdate);
+    }
+}
+
+/// Refresh the water label whenever a bucket is used or refilled.
+fn update_water_label(
+    mut commands: Commands,
+    q_tanks: Query<&WaterTank>,
+    mut last_buckets: Local<Option<u32>>,
+) {
+    let buckets = q_tanks.single().ok().map(|t| t.buckets());
+    if *last_buckets != buckets {
+        *last_buckets = buckets;
+        commands.react().broadcast(WaterUpdate);
+    }
+}
+
+/// Keep the seed bar in sync with prices, the inventory, the bank balance and the current
+/// selection.
+fn update_seed_buttons(
+    mut q_buttons: Query<(&SeedButton, &mut BorderColor, &Children)>,
+    mut q_sprites: Query<&mut ImageNode>,
+    mut q_labels: Query<(&SeedButtonLabel, &mut Text, &mut TextColor)>,
+    q_seed_selection: Reactive<SeedSelection>,
+    q_inventory: Query<&Inventory, With<Player>>,
+    q_bank_account: Query<&BankAccount>,
+    plant_registry: Res<PlantRegistry>,
+) {
+    let Ok(inventory) = q_inventory.single() else {
+        return;
+    };
+    let Ok(bank_account) = q_bank_account.single() else {
+        return;
+    };
+    let Ok((_, seed_selection)) = q_seed_selection.get_single() else {
+        return;
+    };
+    let selected = seed_selection.seed_type().species();
+
+    for (button, mut border_color, children) in q_buttons.iter_mut() {
+        let border = if button.0 == selected {
+            SEED_BUTTON_SELECTED_BORDER
+        } else {
+            SEED_BUTTON_BORDER
+        };
+        border_color.set_if_neq(BorderColor(border));
+
+        let tint = if inventory.count(button.0) > 0 {
+            Color::WHITE
+        } else {
+            SEED_BUTTON_OUT_OF_STOCK
+        };
+        for &child in children {
+            if let Ok(mut sprite) = q_sprites.get_mut(child) {
+                if sprite.color != tint {
+                    sprite.color = tint;
+                }
+            }
+        }
+    }
+
+    for (label, mut text, mut text_color) in q_labels.iter_mut() {
+        let count = inventory.count(label.0);
+        let price = plant_registry.price(label.0);
+        let label_text = format!("{} ${:.2}\n{} left", label.0, price, count);
+        if text.0 != label_text {
+            text.0 = label_text;
+        }
+
+        // Out of stock is only a dead end if more can't be bought either
+        let color = if count > 0 {
+            Color::WHITE
+        } else if bank_account.balance() >= price {
+            SEED_BUTTON_OUT_OF_STOCK
+        } else {
+            SEED_BUTTON_UNAFFORDABLE
+        };
+        text_color.set_if_neq(TextColor(color));
+    }
+}
+
+/// Label the speed buttons and highlight the current game speed.
+fn update_speed_buttons(
+    mut q_buttons: Query<(&SpeedButton, &mut BorderColor)>,
+    mut q_labels: Query<(&SpeedButtonLabel, &mut Text)>,
+    game_speed: Res<GameSpeed>,
+) {
+    for (button, mut border_color) in q_buttons.iter_mut() {
+        let border = if button.0 == *game_speed {
+            SEED_BUTTON_SELECTED_BORDER
+        } else {
+            SEED_BUTTON_BORDER
+        };
+        border_color.set_if_neq(BorderColor(border));
+    }
+
+    for (label, mut text) in q_labels.iter_mut() {
+        let label_text = label.0.to_string();
+        if text.0 != label_text {
+            text.0 = label_text;
+        }
+    }
+}
+
+/// Price the order buttons for the current seed, mark the orders that can't be afforded, and
+/// highlight express delivery when it's on.
+fn update_order_buttons(
+    mut q_buttons: Query<(&OrderButton, &mut BorderColor)>,
+    mut q_labels: Query<(&OrderButtonLabel, &mut Text, &mut TextColor)>,
+    q_seed_selection: Reactive<SeedSelection>,
+    q_bank_account: Query<&BankAccount>,
+    express: Res<ExpressDelivery>,
+    plant_registry: Res<PlantRegistry>,
+) {
+    let Ok((_, seed_selection)) = q_seed_selection.get_single() else {
+        return;
+    };
+    let balance = q_bank_account.single().map_or(0., |b| b.balance());
+    let unit_price = plant_registry.price(seed_selection.seed_type().species());
+
+    for (button, mut border_color) in q_buttons.iter_mut() {
+        let border = if *button == OrderButton::Express && express.0 {
+            SEED_BUTTON_SELECTED_BORDER
+        } else {
+            SEED_BUTTON_BORDER
+        };
+        border_color.set_if_neq(BorderColor(border));
+    }
+
+    for (label, mut text, mut text_color) in q_labels.iter_mut() {
+        let (label_text, affordable) = match label.0 {
+            OrderButton::Express if express.0 => ("Express\non".to_string(), true),
+            OrderButton::Express => ("Express\noff".to_string(), true),
+            button => {
+                let price = order_price(unit_price, button.quantity(), express.0);
+                (
+                    format!("Buy {}\n${:.2}", button.quantity(), price),
+                    balance >= price,
+                )
+            }
+        };
+        if text.0 != label_text {
+            text.0 = label_text;
+        }
+
+        let color = if affordable {
+            Color::WHITE
+        } else {
+            SEED_BUTTON_UNAFFORDABLE
+        };
+        text_color.set_if_neq(TextColor(color));
+    }
+}
+
+/// Show how many of the current seed are in stock and on their way.
+fn update_order_status(
+    mut q_labels: Query<&mut Text, With<OrderStatusLabel>>,
+    q_seed_selection: Reactive<SeedSelection>,
+    q_orders: Query<&MailOrder>,
+) {
+    let Ok((_, seed_selection)) = q_seed_selection.get_single() else {
+        return;
+    };
+    let seed_type = seed_selection.seed_type().species();
+    let on_the_way: u32 = q_orders
+        .iter()
+        .filter(|order| order.plant_type() == seed_type)
+        .map(|order| order.quantity())
+        .sum();
+    let label_text = format!("On the way: {}\n", on_the_way);
+    for mut text in q_labels.iter_mut() {
+        if text.0 != label_text {
+            text.0 = label_text.clone();
+        }
+    }
+}
@@ -0,0 +1,390 @@
+//! Buckets of water thrown from the barn keep plants growing and put out fires.
+
+use crate::asset_tracking::LoadResource;
+use crate::audio::sound_effect;
+use crate::game::despawn::DespawnOnRestart;
+use crate::game::farm::{FarmGrid, RestartGameEvent};
+use crate::game::fire::Burning;
+use crate::game::plant::{GNOME_THROW_RADIUS_PX, GrowthTimer, Plant, PlantType};
+use crate::game::player::{PLAYER_THROW_RADIUS_PX, throw_path, window_to_world};
+use crate::game::replay::ReplayInput;
+use crate::game::smoke::SpawnSmokeEvent;
+use crate::theme::palette::WATER;
+use crate::{AppSystems, DrawSystems, OnPauseSystems, PausableSystems};
+use avian2d::prelude::{LinearVelocity, RigidBody};
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_vector_shapes::prelude::*;
+use serde::{Deserialize, Serialize};
+
+const WATER_Z_LAYER: f32 = 2.0;
+const WATER_MOVE_SPEED: f32 = 300.;
+const WATER_POINT_EPSILON: f32 = 5.0;
+const WATER_RADIUS_PX: f32 = 8.;
+
+const TANK_CAPACITY: f32 = 3.;
+const TANK_REFILL_PER_S: f32 = 0.2;
+const BUCKET_SIZE: f32 = 1.;
+
+/// How much water a seedling starts with, out of 1.
+const SEEDLING_HYDRATION: f32 = 0.5;
+/// How much water a growing plant drinks each second.
+const HYDRATION_USE_PER_S: f32 = 0.25;
+/// How fast a plant grows without water, as a fraction of its watered speed.
+const DRY_GROWTH_RATE: f32 = 0.25;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Water>();
+    app.register_type::<WaterTank>();
+    app.register_type::<Hydration>();
+
+    app.register_type::<WaterAssets>();
+    app.load_resource::<WaterAssets>();
+
+    app.add_event::<WaterClickEvent>();
+
+    app.add_systems(Update, freeze_water.in_set(OnPauseSystems));
+    app.add_systems(
+        Update,
+        on_right_click
+            .run_if(input_just_pressed(MouseButton::Right))
+            .in_set(AppSystems::RecordInput)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        FixedUpdate,
+        (
+            throw_water.run_if(resource_exists::<WaterAssets>),
+            move_water,
+            refill_tanks,
+            restart_tanks,
+        )
+            .in_set(PausableSystems),
+    );
+    app.add_systems(Update, draw_water.in_set(DrawSystems));
+}
+
+fn water(path: Vec<Vec2>, origin: Vec2) -> impl Bundle {
+    (
+        Name::new("Water"),
+        Water { path },
+        DespawnOnRestart,
+        Transform::from_translation(origin.extend(WATER_Z_LAYER)),
+        RigidBody::Kinematic,
+        LinearVelocity::ZERO,
+    )
+}
+
+/// A bucket of water in flight, and the points it has still to pass through.
+#[derive(Component, Debug, Clone, PartialEq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Water {
+    path: Vec<Vec2>,
+}
+
+/// A bucket of water in flight as it was when the game was saved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedWater {
+    position: (f32, f32),
+    path: Vec<(f32, f32)>,
+}
+
+impl SavedWater {
+    pub fn new(transform: &Transform, water: &Water) -> Self {
+        Self {
+            position: transform.translation.xy().into(),
+            path: water.path.iter().map(|p| (*p).into()).collect(),
+        }
+    }
+
+    pub fn spawn(&self, commands: &mut Commands) {
+        commands.spawn(water(
+            self.path.iter().map(|p| Vec2::from(*p)).collect(),
+            self.position.into(),
+        ));
+    }
+}
+
+/// The water stored at the barn, refilling slowly.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct WaterTank {
+    level: f32,
+}
+
+impl Default for WaterTank {
+    fn default() -> Self {
+        Self {
+            level: TANK_CAPACITY,
+        }
+    }
+}
+
+impl WaterTank {
+    /// The number of whole buckets that can be thrown right now.
+    pub fn buckets(&self) -> u32 {
+        (self.level / BUCKET_SIZE) as u32
+    }
+
+    pub fn capacity(&self) -> u32 {
+        (TANK_CAPACITY / BUCKET_SIZE) as u32
+    }
+
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    pub fn set_level(&mut self, level: f32) {
+        self.level = level.clamp(0., TANK_CAPACITY);
+    }
+}
+
+/// How much water a plant has, from 0 (dry) to 1. Dry plants grow slowly.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Hydration(f32);
+
+impl Default for Hydration {
+    fn default() -> Self {
+        Self(SEEDLING_HYDRATION)
+    }
+}
+
+impl Hydration {
+    pub fn with_level(level: f32) -> Self {
+        Self(level.clamp(0., 1.))
+    }
+
+    pub fn level(&self) -> f32 {
+        self.0
+    }
+
+    pub fn is_dry(&self) -> bool {
+        self.0 <= 0.
+    }
+
+    /// How fast the plant grows, as a fraction of its full speed.
+    pub fn growth_rate(&self) -> f32 {
+        if self.is_dry() { DRY_GROWTH_RATE } else { 1. }
+    }
+
+    /// Drink the water a growing plant needs for `delta_s` seconds.
+    pub fn drink(&mut self, delta_s: f32) {
+        self.0 = (self.0 - HYDRATION_USE_PER_S * delta_s).max(0.);
+    }
+
+    fn fill(&mut self) {
+        self.0 = 1.;
+    }
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+struct WaterAssets {
+    #[dependency]
+    throw_sound: Handle<AudioSource>,
+    #[dependency]
+    invalid_sound: Handle<AudioSource>,
+}
+
+impl FromWorld for WaterAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            throw_sound: assets.load("audio/sound_effects/woosh/woosh1.ogg"),
+            invalid_sound: assets.load("audio/sound_effects/invalid.ogg"),
+        }
+    }
+}
+
+/// A request to throw a bucket of water at a point on the farm.
+#[derive(Event, Debug, Default)]
+pub struct WaterClickEvent(pub Vec2);
+
+fn on_right_click(
+    q_windows: Query<&Window, With<PrimaryWindow>>,
+    q_camera: Query<(&Camera, &GlobalTransform)>,
+    mut events: EventWriter<ReplayInput>,
+) {
+    let (Ok(window), Ok((camera, camera_transform))) = (q_windows.single(), q_camera.single())
+    else {
+        return;
+    };
+    if let Some(world_position) = window
+        .cursor_position()
+        .and_then(|p| window_to_world(p, camera, camera_transform))
+    {
+        events.write(ReplayInput::Water(world_position.into()));
+    }
+}
+
+/// Throw a bucket from the barn to the clicked tile, relayed by gnomes like seeds are.
+fn throw_water(
+    mut commands: Commands,
+    mut click_events: EventReader<WaterClickEvent>,
+    mut q_tanks: Query<(&Transform, &mut WaterTank)>,
+    q_grown_plants: Query<(&Transform, &Plant), Without<GrowthTimer>>,
+    water_assets: Res<WaterAssets>,
+) {
+    for click_event in click_events.read() {
+        let Ok((tank_transform, mut tank)) = q_tanks.single_mut() else {
+            warn!("No water tank!");
+            return;
+        };
+
+        let tank_position = tank_transform.translation.xy().as_ivec2();
+        let gnome_positions: Vec<IVec2> = q_grown_plants
+            .iter()
+            .filter(|(_, p)| p.plant_type() == PlantType::Gnome)
+            .map(|(t, _)| t.translation.xy().as_ivec2())
+            .collect();
+        let path = FarmGrid::tile_at(click_event.0).and_then(|tile| {
+            throw_path(
+                tank_position,
+                gnome_positions,
+                FarmGrid::tile_center(tile).as_ivec2(),
+                PLAYER_THROW_RADIUS_PX,
+                GNOME_THROW_RADIUS_PX,
+            )
+        });
+
+        let Some(path) = path.filter(|_| tank.level >= BUCKET_SIZE) else {
+            info!(
+                "Can't throw water to {:?} (tank at {})",
+                click_event.0, tank.level
+            );
+            commands.spawn((
+                sound_effect(water_assets.invalid_sound.clone()),
+                Transform::from_translation(click_event.0.extend(0.)),
+            ));
+            continue;
+        };
+
+        tank.level -= BUCKET_SIZE;
+        info!("Throwing water along {:?}", path);
+        commands.spawn((
+            sound_effect(water_assets.throw_sound.clone()),
+            Transform::from_translation(tank_transform.translation),
+        ));
+        commands.spawn(water(
+            path[1..].iter().map(|p| p.as_vec2()).collect(),
+            tank_position.as_vec2(),
+        ));
+    }
+}
+
+fn freeze_water(mut q_water: Query<&mut LinearVelocity, With<Water>>) {
+    for mut vel in q_water.iter_mut() {
+        *vel = LinearVelocity::ZERO;
+    }
+}
+
+fn move_water(
+    mut commands: Commands,
+    mut q_water: Query<(Entity, &Transform, &mut LinearVelocity, &mut Water)>,
+    mut q_plants: Query<(&Transform, Option<&mut Hydration>, Has<Burning>), With<Plant>>,
+    mut spawn_smoke_events: EventWriter<SpawnSmokeEvent>,
+    farm_grid: Res<FarmGrid>,
+) {
+    for (entity, transform, mut vel, mut water) in &mut q_water {
+        let position = transform.translation.xy();
+        let Some(&target) = water.path.first() else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        let vec_to_target = target - position;
+        if vec_to_target.length() >= WATER_POINT_EPSILON {
+            *vel = LinearVelocity(WATER_MOVE_SPEED * vec_to_target.normalize());
+            continue;
+        }
+
+        water.path.remove(0);
+        if !water.path.is_empty() {
+            continue;
+        }
+
+        // Splash down on the tile
+        commands.entity(entity).despawn();
+        let Some(plant_entity) = FarmGrid::tile_at(target).and_then(|t| farm_grid.occupant(t))
+        else {
+            continue;
+        };
+        let Ok((plant_transform, hydration, is_burning)) = q_plants.get_mut(plant_entity) else {
+            continue;
+        };
+        if let Some(mut hydration) = hydration {
+            hydration.fill();
+        }
+        if is_burning {
+            info!("Water put out the fire on {:?}", plant_entity);
+            commands.entity(plant_entity).try_remove::<Burning>();
+            spawn_smoke_events.write(SpawnSmokeEvent(plant_transform.translation));
+        }
+    }
+}
+
+fn refill_tanks(mut q_tanks: Query<&mut WaterTank>, time: Res<Time>) {
+    for mut tank in &mut q_tanks {
+        if tank.level < TANK_CAPACITY {
+            tank.level = (tank.level + TANK_REFILL_PER_S * time.delta_secs()).min(TANK_CAPACITY);
+        }
+    }
+}
+
+fn restart_tanks(
+    mut restart_events: EventReader<RestartGameEvent>,
+    mut q_tanks: Query<&mut WaterTank>,
+) {
+    if restart_events.read().count() > 0 {
+        for mut tank in &mut q_tanks {
+            *tank = WaterTank::default();
+        }
+    }
+}
+
+fn draw_water(mut painter: ShapePainter, q_water: Query<&Transform, With<Water>>) {
+    painter.hollow = false;
+    painter.color = WATER;
+    for transform in q_water {
+        painter.transform.translation = transform.translation;
+        painter.circle(WATER_RADIUS_PX);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::sim::Sim;
+
+    /// The centre of the tile containing (100, 0).
+    const TILE: Vec2 = Vec2::new(128., 64.);
+
+    #[test]
+    fn unwatered_daisy_grows_slowly_but_blooms() {
+        let mut sim = Sim::new();
+        sim.click(Vec2::new(100., 0.));
+        sim.sown_at(TILE);
+
+        // 2 s of growth on the seedling's own water, then the last second takes four
+        sim.advance(3.1);
+        assert!(!sim.plant_at(TILE).unwrap().grown);
+        sim.advance(3.5);
+        assert!(sim.plant_at(TILE).unwrap().grown);
+    }
+
+    #[test]
+    fn watered_daisy_blooms_after_three_seconds() {
+        let mut sim = Sim::new();
+        sim.click(Vec2::new(100., 0.));
+        let daisy = sim.sown_at(TILE);
+        assert_eq!(daisy.plant_type, PlantType::Daisy);
+
+        // Keep it watered, since a seedling only has enough water for 2 s of growth
+        sim.water(TILE);
+        sim.advance(2.9);
+        assert!(!sim.plant_at(TILE).unwrap().grown);
+        sim.advance(0.2);
+        assert!(sim.plant_at(TILE).unwrap().grown);
+    }
+}
@@ -0,0 +1,399 @@
+//! Enemies arrive in waves described by `assets/data/script.waves.ron`.
+
+use crate::PausableSystems;
+use crate::asset_tracking::LoadResource;
+use crate::game::enemy::{Enemy, EnemyKind, enemy};
+use crate::game::enemy_registry::EnemyRegistry;
+use crate::game::farm::{FARM_SIZE_PX, RestartGameEvent, TILE_SIZE_PX};
+use crate::game::plant::Plant;
+use crate::game::rng::GameRng;
+use crate::screens::Screen;
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+const WAVE_SCRIPT_PATH: &str = "data/script.waves.ron";
+
+/// How far outside the farm enemies appear.
+const SPAWN_MARGIN_PX: f32 = TILE_SIZE_PX / 2.;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<WaveScript>();
+    app.register_type::<WaveState>();
+    app.register_asset_loader(WaveScriptLoader);
+    app.load_resource_from_path::<WaveScript>(WAVE_SCRIPT_PATH);
+
+    app.init_resource::<WaveState>();
+    app.add_event::<WaveStartedEvent>();
+    app.add_event::<WaveClearedEvent>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_waves);
+    app.add_systems(
+        FixedUpdate,
+        (restart_waves, tick_waves)
+            .chain()
+            .run_if(resource_exists::<WaveScript>.and(resource_exists::<EnemyRegistry>))
+            .in_set(PausableSystems),
+    );
+}
+
+/// The waves of the game, in order. The last wave repeats forever.
+#[derive(Resource, Asset, Clone, Debug, Deserialize, Reflect)]
+#[reflect(Resource)]
+pub struct WaveScript {
+    waves: Vec<Wave>,
+}
+
+#[derive(Clone, Debug, Deserialize, Reflect)]
+struct Wave {
+    /// Time before this wave starts, counted only while something is planted.
+    break_s: f32,
+    /// Money paid when every enemy of this wave has been dealt with.
+    #[serde(default)]
+    bonus: f32,
+    groups: Vec<SpawnGroup>,
+}
+
+/// A batch of one kind of enemy coming from one edge of the farm.
+#[derive(Clone, Debug, Deserialize, Reflect)]
+struct SpawnGroup {
+    enemy: EnemyKind,
+    count: usize,
+    edge: SpawnEdge,
+    /// Time after the wave starts before the first enemy of this group appears.
+    #[serde(default)]
+    delay_s: f32,
+    /// Time between enemies of this group.
+    interval_s: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+enum SpawnEdge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl SpawnEdge {
+    /// A random position just outside this edge of the farm.
+    fn random_position(&self, rng: &mut impl Rng) -> Vec2 {
+        let half_size = FARM_SIZE_PX / 2.;
+        let along: f32 = rng.gen_range(-0.9..0.9);
+        match self {
+            SpawnEdge::Left => Vec2::new(-half_size.x - SPAWN_MARGIN_PX, along * half_size.y),
+            SpawnEdge::Right => Vec2::new(half_size.x + SPAWN_MARGIN_PX, along * half_size.y),
+            SpawnEdge::Top => Vec2::new(along * half_size.x, half_size.y + SPAWN_MARGIN_PX),
+            SpawnEdge::Bottom => Vec2::new(along * half_size.x, -half_size.y - SPAWN_MARGIN_PX),
+        }
+    }
+}
+
+/// Progress through the [`WaveScript`].
+#[derive(Resource, Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct WaveState {
+    /// The current (or upcoming) wave, starting from 1.
+    wave_number: usize,
+    phase: WavePhase,
+    /// Enemies of the current wave that are yet to spawn or still alive.
+    enemies_left: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Reflect)]
+enum WavePhase {
+    /// Nothing has started yet.
+    #[default]
+    Idle,
+    /// Waiting for the next wave to start.
+    Break(Timer),
+    /// Enemies are spawning or still on the farm.
+    Active(Vec<PendingGroup>),
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect)]
+struct PendingGroup {
+    enemy: EnemyKind,
+    edge: SpawnEdge,
+    remaining: usize,
+    timer: Timer,
+    interval_s: f32,
+}
+
+impl WaveState {
+    /// A one-line description for the HUD, e.g. "Wave 3 — 5 rats incoming".
+    pub fn summary(&self) -> String {
+        match &self.phase {
+            WavePhase::Idle => String::new(),
+            WavePhase::Break(timer) => format!(
+                "Wave {} in {:.0}s",
+                self.wave_number,
+                timer.remaining_secs().ceil()
+            ),
+            WavePhase::Active(groups) => {
+                // Name the enemy if the whole wave is one kind
+                let mut kinds = groups.iter().map(|g| g.enemy);
+                let enemy_name = match kinds.next() {
+                    Some(kind) if kinds.all(|k| k == kind) => kind.to_string(),
+                    _ => "enemy".to_string(),
+                };
+                let enemy_name = match (self.enemies_left, enemy_name.strip_suffix('y')) {
+                    (1, _) => enemy_name,
+                    (_, Some(stem)) => format!("{}ies", stem),
+                    _ => format!("{}s", enemy_name),
+                };
+                format!(
+                    "Wave {} — {} {} incoming",
+                    self.wave_number, self.enemies_left, enemy_name
+                )
+            }
+        }
+    }
+
+    fn start_break(&mut self, wave_script: &WaveScript, wave_number: usize) {
+        let wave = wave_script.wave(wave_number);
+        self.wave_number = wave_number;
+        self.enemies_left = 0;
+        self.phase = WavePhase::Break(Timer::from_seconds(wave.break_s, TimerMode::Once));
+    }
+}
+
+/// [`WaveState`] as it was when the game was saved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedWaveState {
+    wave_number: usize,
+    phase: SavedWavePhase,
+    enemies_left: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum SavedWavePhase {
+    Idle,
+    Break { remaining_s: f32 },
+    Active(Vec<SavedGroup>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SavedGroup {
+    enemy: EnemyKind,
+    edge: SpawnEdge,
+    remaining: usize,
+    /// Time until the next enemy of this group appears.
+    next_spawn_s: f32,
+    interval_s: f32,
+}
+
+impl WaveState {
+    pub fn save(&self) -> SavedWaveState {
+        let phase = match &self.phase {
+            WavePhase::Idle => SavedWavePhase::Idle,
+            WavePhase::Break(timer) => SavedWavePhase::Break {
+                remaining_s: timer.remaining_secs(),
+            },
+            WavePhase::Active(groups) => SavedWavePhase::Active(
+                groups
+                    .iter()
+                    .map(|g| SavedGroup {
+                        enemy: g.enemy,
+                        edge: g.edge,
+                        remaining: g.remaining,
+                        next_spawn_s: g.timer.remaining_secs(),
+                        interval_s: g.interval_s,
+                    })
+                    .collect(),
+            ),
+        };
+        SavedWaveState {
+            wave_number: self.wave_number,
+            phase,
+            enemies_left: self.enemies_left,
+        }
+    }
+
+    pub fn load(saved: &SavedWaveState) -> Self {
+        let phase = match &saved.phase {
+            SavedWavePhase::Idle => WavePhase::Idle,
+            SavedWavePhase::Break { remaining_s } => {
+                WavePhase::Break(Timer::from_seconds(*remaining_s, TimerMode::Once))
+            }
+            SavedWavePhase::Active(groups) => WavePhase::Active(
+                groups
+                    .iter()
+                    .map(|g| PendingGroup {
+                        enemy: g.enemy,
+                        edge: g.edge,
+                        remaining: g.remaining,
+                        timer: Timer::from_seconds(g.next_spawn_s, TimerMode::Once),
+                        interval_s: g.interval_s,
+                    })
+                    .collect(),
+            ),
+        };
+        Self {
+            wave_number: saved.wave_number,
+            phase,
+            enemies_left: saved.enemies_left,
+        }
+    }
+}
+
+impl WaveScript {
+    /// The wave with the given 1-based number. Numbers past the end repeat the last wave.
+    fn wave(&self, wave_number: usize) -> &Wave {
+        let index = wave_number.saturating_sub(1).min(self.waves.len() - 1);
+        &self.waves[index]
+    }
+}
+
+#[derive(Event, Debug)]
+pub struct WaveStartedEvent {
+    pub wave_number: usize,
+}
+
+#[derive(Event, Debug)]
+pub struct WaveClearedEvent {
+    pub wave_number: usize,
+    pub bonus: f32,
+}
+
+#[derive(Default)]
+struct WaveScriptLoader;
+
+#[derive(Debug, Error)]
+enum WaveScriptLoaderError {
+    #[error("Could not read wave script: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse wave script: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Wave script has no waves")]
+    NoWaves,
+}
+
+impl AssetLoader for WaveScriptLoader {
+    type Asset = WaveScript;
+    type Settings = ();
+    type Error = WaveScriptLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let wave_script: WaveScript = ron::de::from_bytes(&bytes)?;
+        if wave_script.waves.is_empty() {
+            return Err(WaveScriptLoaderError::NoWaves);
+        }
+        Ok(wave_script)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["waves.ron"]
+    }
+}
+
+fn reset_waves(mut wave_state: ResMut<WaveState>) {
+    *wave_state = WaveState::default();
+}
+
+fn restart_waves(
+    mut restart_events: EventReader<RestartGameEvent>,
+    mut wave_state: ResMut<WaveState>,
+) {
+    if restart_events.read().count() > 0 {
+        info!("Restarting waves");
+        *wave_state = WaveState::default();
+    }
+}
+
+fn tick_waves(
+    mut commands: Commands,
+    mut wave_state: ResMut<WaveState>,
+    wave_script: Res<WaveScript>,
+    q_enemies: Query<(), With<Enemy>>,
+    q_plants: Query<(), With<Plant>>,
+    mut wave_started_events: EventWriter<WaveStartedEvent>,
+    mut wave_cleared_events: EventWriter<WaveClearedEvent>,
+    enemy_registry: Res<EnemyRegistry>,
+    mut rng: ResMut<GameRng>,
+    time: Res<Time>,
+) {
+    let wave_number = wave_state.wave_number;
+    match &mut wave_state.phase {
+        WavePhase::Idle => {
+            wave_state.start_break(&wave_script, 1);
+        }
+        WavePhase::Break(timer) => {
+            // Don't attack an empty farm
+            if q_plants.is_empty() {
+                return;
+            }
+            timer.tick(time.delta());
+            if !timer.finished() {
+                return;
+            }
+
+            let wave = wave_script.wave(wave_number);
+            info!("Wave {} starting", wave_number);
+            let groups: Vec<PendingGroup> = wave
+                .groups
+                .iter()
+                .map(|g| PendingGroup {
+                    enemy: g.enemy,
+                    edge: g.edge,
+                    remaining: g.count,
+                    timer: Timer::from_seconds(g.delay_s, TimerMode::Once),
+                    interval_s: g.interval_s,
+                })
+                .collect();
+            wave_state.enemies_left = groups.iter().map(|g| g.remaining).sum();
+            wave_state.phase = WavePhase::Active(groups);
+            wave_started_events.write(WaveStartedEvent { wave_number });
+        }
+        WavePhase::Active(groups) => {
+            for group in groups.iter_mut().filter(|g| g.remaining > 0) {
+                group.timer.tick(time.delta());
+                if group.timer.just_finished() {
+                    let position = group.edge.random_position(&mut *rng);
+                    if let Some(definition) = enemy_registry.get(group.enemy) {
+                        info!("Spawning a {} at {:?}", group.enemy, position);
+                        commands.spawn(enemy(position.extend(1.), definition));
+                    } else {
+                        warn!("No bestiary entry for {}", group.enemy);
+                    }
+                    group.remaining -= 1;
+                    group.timer = Timer::from_seconds(group.interval_s, TimerMode::Once);
+                }
+            }
+
+            let still_to_spawn: usize = groups.iter().map(|g| g.remaining).sum();
+            wave_state.enemies_left = still_to_spawn + q_enemies.iter().count();
+            if wave_state.enemies_left == 0 {
+                let bonus = wave_script.wave(wave_number).bonus;
+                info!("Wave {} cleared, bonus {}", wave_number, bonus);
+                wave_cleared_events.write(WaveClearedEvent { wave_number, bonus });
+                wave_state.start_break(&wave_script, wave_number + 1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wave_script_parses_and_every_wave_has_enemies() {
+        let wave_script: WaveScript =
+            ron::from_str(include_str!("../../assets/data/script.waves.ron"))
+                .expect("Could not parse wave script");
+        assert!(!wave_script.waves.is_empty());
+        for wave in &wave_script.waves {
+            assert!(wave.groups.iter().map(|group| group.count).sum::<usize>() > 0);
+        }
+    }
+}
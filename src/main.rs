@@ -59,9 +59,19 @@ impl Plugin for AppPlugin {
             theme::plugin,
         ));
 
-        // Order new `AppSystems` variants by adding them here:
+        configure_schedules(app);
+
+        // Spawn the main camera.
+        app.add_systems(Startup, spawn_camera);
+    }
+}
+
+/// Set up the system sets and states that the game's systems are scheduled in.
+fn configure_schedules(app: &mut App) {
+    // Order new `AppSystems` variants by adding them here:
+    for schedule in [Update.intern(), FixedUpdate.intern()] {
         app.configure_sets(
-            Update,
+            schedule,
             (
                 AppSystems::TickTimers,
                 AppSystems::RecordInput,
@@ -69,18 +79,23 @@ impl Plugin for AppPlugin {
             )
                 .chain(),
         );
+    }
 
-        // Set up the `Pause` state.
-        app.init_state::<Pause>();
-        app.configure_sets(Update, PausableSystems.run_if(in_state(Pause(false))));
-        app.configure_sets(Update, OnPauseSystems.run_if(in_state(Pause(true))));
+    // Set up the `Pause` state.
+    app.init_state::<Pause>();
+    app.configure_sets(Update, PausableSystems.run_if(in_state(Pause(false))));
+    app.configure_sets(Update, OnPauseSystems.run_if(in_state(Pause(true))));
 
-        // Spawn the main camera.
-        app.add_systems(Startup, spawn_camera);
-    }
+    // Gameplay runs on a fixed timestep, after the inputs for the tick have been recorded.
+    app.configure_sets(
+        FixedUpdate,
+        PausableSystems
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Pause(false))),
+    );
 }
 
-/// High-level groupings of systems for the app in the `Update` schedule.
+/// High-level groupings of systems for the app in the `Update` and `FixedUpdate` schedules.
 /// When adding a new variant, make sure to order it in the `configure_sets`
 /// call above.
 #[derive(SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
@@ -106,6 +121,10 @@ struct OnPauseSystems;
 #[derive(SystemSet, Copy, Clone, Eq, PartialEq, Hash, Debug)]
 struct PausableSystems;
 
+/// A system set for systems that draw shapes, which need a renderer.
+#[derive(SystemSet, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+struct DrawSystems;
+
 fn spawn_camera(mut commands: Commands) {
     commands.spawn((Name::new("Camera"), Camera2d));
 }
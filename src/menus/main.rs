@@ -2,31 +2,49 @@
 
 use bevy::prelude::*;
 
-use crate::{asset_tracking::ResourceHandles, menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    asset_tracking::ResourceHandles,
+    game::save::{PendingSave, SaveGame},
+    menus::Menu,
+    screens::Screen,
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Main), spawn_main_menu);
 }
 
 fn spawn_main_menu(mut commands: Commands) {
-    commands.spawn((
-        widget::ui_root("Main Menu"),
-        GlobalZIndex(2),
-        StateScoped(Menu::Main),
-        #[cfg(not(target_family = "wasm"))]
-        children![
-            widget::button("Play", enter_loading_or_gameplay_screen),
-            widget::button("Settings", open_settings_menu),
-            widget::button("Credits", open_credits_menu),
-            widget::button("Exit", exit_app),
-        ],
-        #[cfg(target_family = "wasm")]
-        children![
-            widget::button("Play", enter_loading_or_gameplay_screen),
-            widget::button("Settings", open_settings_menu),
-            widget::button("Credits", open_credits_menu),
-        ],
-    ));
+    let main_menu = commands
+        .spawn((
+            widget::ui_root("Main Menu"),
+            GlobalZIndex(2),
+            StateScoped(Menu::Main),
+            #[cfg(not(target_family = "wasm"))]
+            children![
+                widget::button("Play", enter_loading_or_gameplay_screen),
+                widget::button("Settings", open_settings_menu),
+                widget::button("Credits", open_credits_menu),
+                widget::button("Exit", exit_app),
+            ],
+            #[cfg(target_family = "wasm")]
+            children![
+                widget::button("Play", enter_loading_or_gameplay_screen),
+                widget::button("Settings", open_settings_menu),
+                widget::button("Credits", open_credits_menu),
+            ],
+        ))
+        .id();
+
+    // Offer to pick up the last game where it was left
+    if SaveGame::exists() {
+        let continue_button = commands
+            .spawn(widget::button("Continue", continue_saved_game))
+            .id();
+        commands
+            .entity(main_menu)
+            .insert_children(0, &[continue_button]);
+    }
 }
 
 fn enter_loading_or_gameplay_screen(
@@ -41,6 +59,21 @@ fn enter_loading_or_gameplay_screen(
     }
 }
 
+fn continue_saved_game(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    resource_handles: Res<ResourceHandles>,
+    next_screen: ResMut<NextState<Screen>>,
+) {
+    match SaveGame::read() {
+        Ok(save) => {
+            commands.insert_resource(PendingSave(save));
+            enter_loading_or_gameplay_screen(trigger, resource_handles, next_screen);
+        }
+        Err(e) => warn!("{}", e),
+    }
+}
+
 fn open_settings_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Settings);
 }
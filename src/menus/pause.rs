@@ -2,10 +2,10 @@
 
 use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 
-use crate::{menus::Menu, theme::widget};
+use crate::{game::ledger::spawn_ledger_panel, menus::Menu, theme::widget};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(Menu::Pause), spawn_pause_menu);
+    app.add_systems(OnEnter(Menu::Pause), (spawn_pause_menu, spawn_ledger_panel));
     app.add_systems(
         Update,
         go_back.run_if(in_state(Menu::Pause).and(input_just_pressed(KeyCode::Escape))),
@@ -2,9 +2,17 @@
 //!
 //! Additional settings and accessibility options should go here.
 
-use bevy::{audio::Volume, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
+use bevy::{
+    audio::Volume, ecs::spawn::SpawnWith, input::common_conditions::input_just_pressed, prelude::*,
+    ui::Val::*,
+};
 
-use crate::{menus::Menu, screens::Screen, theme::prelude::*};
+use crate::{
+    game::rng::{GameRng, NextSeed, random_seed},
+    menus::Menu,
+    screens::Screen,
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Settings), spawn_settings_menu);
@@ -18,22 +26,27 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         update_global_volume_label.run_if(in_state(Menu::Settings)),
     );
+
+    app.register_type::<SeedLabel>();
+    app.add_systems(Update, update_seed_label.run_if(in_state(Menu::Settings)));
 }
 
-fn spawn_settings_menu(mut commands: Commands) {
+fn spawn_settings_menu(mut commands: Commands, screen: Res<State<Screen>>) {
+    // The seed of a game in progress is fixed, or its replay would no longer match it
+    let seed_changeable = screen.get() != &Screen::Gameplay;
     commands.spawn((
         widget::ui_root("Settings Menu"),
         GlobalZIndex(2),
         StateScoped(Menu::Settings),
         children![
             widget::header("Settings"),
-            settings_grid(),
+            settings_grid(seed_changeable),
             widget::button("Back", go_back_on_click),
         ],
     ));
 }
 
-fn settings_grid() -> impl Bundle {
+fn settings_grid(seed_changeable: bool) -> impl Bundle {
     (
         Name::new("Settings Grid"),
         Node {
@@ -52,6 +65,14 @@ fn settings_grid() -> impl Bundle {
                 }
             ),
             global_volume_widget(),
+            (
+                widget::label("Seed"),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                }
+            ),
+            seed_widget(seed_changeable),
         ],
     )
 }
@@ -79,6 +100,34 @@ fn global_volume_widget() -> impl Bundle {
     )
 }
 
+fn seed_widget(changeable: bool) -> impl Bundle {
+    (
+        Name::new("Seed Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            if changeable {
+                parent.spawn(widget::button_small("-", lower_seed));
+            }
+            parent.spawn((
+                Name::new("Current Seed"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), SeedLabel)],
+            ));
+            if changeable {
+                parent.spawn(widget::button_small("+", raise_seed));
+                parent.spawn(widget::button_small("?", randomize_seed));
+            }
+        })),
+    )
+}
+
 const MIN_VOLUME: f32 = 0.0;
 const MAX_VOLUME: f32 = 3.0;
 
@@ -96,6 +145,36 @@ fn raise_global_volume(_: Trigger<Pointer<Click>>, mut global_volume: ResMut<Glo
 #[reflect(Component)]
 struct GlobalVolumeLabel;
 
+fn lower_seed(_: Trigger<Pointer<Click>>, mut next_seed: ResMut<NextSeed>) {
+    next_seed.0 = next_seed.0.wrapping_sub(1);
+}
+
+fn raise_seed(_: Trigger<Pointer<Click>>, mut next_seed: ResMut<NextSeed>) {
+    next_seed.0 = next_seed.0.wrapping_add(1);
+}
+
+fn randomize_seed(_: Trigger<Pointer<Click>>, mut next_seed: ResMut<NextSeed>) {
+    next_seed.0 = random_seed();
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct SeedLabel;
+
+fn update_seed_label(
+    screen: Res<State<Screen>>,
+    next_seed: Res<NextSeed>,
+    rng: Res<GameRng>,
+    mut label: Single<&mut Text, With<SeedLabel>>,
+) {
+    let seed = if screen.get() == &Screen::Gameplay {
+        rng.seed()
+    } else {
+        next_seed.0
+    };
+    label.0 = seed.to_string();
+}
+
 fn update_global_volume_label(
     global_volume: Res<GlobalVolume>,
     mut label: Single<&mut Text, With<GlobalVolumeLabel>>,
@@ -21,6 +21,10 @@ pub const PLANT_OUTLINE: Color = Color::srgba(0.051, 0.302, 0.282, 0.2);
 pub const PLANT_GROWTH_BAR_OUTLINE: Color = Color::srgb(0.051, 0.302, 0.282);
 /// #96f5d9
 pub const PLANT_GROWTH_FOREGROUND: Color = Color::srgb(0.588, 0.961, 0.851);
+/// #e7c340
+pub const PLANT_GROWTH_THIRSTY: Color = Color::srgb(0.906, 0.765, 0.251);
+/// #6299d1
+pub const WATER: Color = Color::srgb(0.384, 0.600, 0.820);
 
 /// #db410e
 pub const ENEMY_EAT_OUTLINE: Color = Color::srgba(0.859, 0.255, 0.055, 0.0);
@@ -40,10 +44,50 @@ pub const PLAYER_THROW_OUTLINE: Color = Color::srgba(0.051, 0.302, 0.282, 0.5);
 
 /// #076237
 pub const GNOME_THROW_OUTLINE: Color = Color::srgba(0.027, 0.384, 0.216, 0.5);
+/// #000000
+pub const THROW_NETWORK_UNREACHABLE: Color = Color::srgba(0.0, 0.0, 0.0, 0.25);
 /// #076237
 pub const ENDGAME_BUTTON_BACKGROUND: Color = Color::srgb(0.027, 0.384, 0.216);
 
+/// #96f5d9
+pub const TILE_HOVER_FREE: Color = Color::srgba(0.588, 0.961, 0.851, 0.6);
+/// #db410e
+pub const TILE_HOVER_OCCUPIED: Color = Color::srgba(0.859, 0.255, 0.055, 0.6);
+
+/// #5ec26a
+pub const THROW_PREVIEW_VALID: Color = Color::srgb(0.369, 0.761, 0.416);
+/// #db410e
+pub const THROW_PREVIEW_INVALID: Color = Color::srgb(0.859, 0.255, 0.055);
+/// #f5d596
+pub const THROW_QUEUED: Color = Color::srgb(0.961, 0.835, 0.588);
+
+/// #000000
+pub const SEED_SHADOW: Color = Color::srgba(0.0, 0.0, 0.0, 0.3);
+
+/// #1c1b44
+pub const SEED_BUTTON_BORDER: Color = Color::srgb(0.110, 0.106, 0.267);
+/// #e7c340
+pub const SEED_BUTTON_SELECTED_BORDER: Color = Color::srgb(0.906, 0.765, 0.251);
+/// #ffffff
+pub const SEED_BUTTON_OUT_OF_STOCK: Color = Color::srgba(1.0, 1.0, 1.0, 0.35);
+/// #d95763
+pub const SEED_BUTTON_UNAFFORDABLE: Color = Color::srgb(0.851, 0.341, 0.388);
+
+/// #f7e26b
+pub const DAISY_CHAIN: Color = Color::srgb(0.969, 0.886, 0.420);
+
+/// #ff9a5c
+pub const BURNING_TINT: Color = Color::srgb(1.0, 0.604, 0.361);
+
 /// #ca632d
 pub const LOSER_BACKGROUND: Color = Color::srgb(0.792, 0.388, 0.176);
 /// #bce7ff
 pub const WINNER_BACKGROUND: Color = Color::srgb(0.737, 0.906, 1.0);
+
+/// #96f5d9
+pub const SEED_PICKUP: Color = Color::srgb(0.588, 0.961, 0.851);
+
+/// #5ec26a
+pub const LEDGER_INCOME: Color = Color::srgb(0.369, 0.761, 0.416);
+/// #db410e
+pub const LEDGER_EXPENSE: Color = Color::srgb(0.859, 0.255, 0.055);